@@ -3,13 +3,15 @@ use bevy::window::{Window, WindowPlugin};
 
 use projet_essaim::config::{MAP_WIDTH, MAP_HEIGHT, SEED};
 use projet_essaim::map::Map;
-use projet_essaim::robot::{Robot, RobotRole, Direction};
+use projet_essaim::robot::{Robot, RobotRole, Direction, RoleRegistry};
 use projet_essaim::station::Station;
+use projet_essaim::simulation::Simulation;
 use projet_essaim::resources::gui::{
     setup_simulation,
     tick_simulation,
     camera_pan_system,
     update_legend_counts,
+    update_structure_sprites,
     update_fog_of_war,
     update_tick_counter,
     update_window_title,
@@ -23,29 +25,29 @@ use projet_essaim::resources::gui::{
     SimulationPaused,
     TILE_SIZE,
     update_speed_indicator,
+    update_robot_tooltip,
 };
 
 fn main() {
-    let map = Map::new(MAP_WIDTH, MAP_HEIGHT, SEED);
-
     let station_x = MAP_WIDTH / 2;
     let station_y = MAP_HEIGHT / 2;
+    let (map, connectivity) = match Map::try_new(MAP_WIDTH, MAP_HEIGHT, SEED, (station_x, station_y)) {
+        Ok(generated) => generated,
+        Err(error) => {
+            eprintln!("Invalid map: {}", error);
+            std::process::exit(1);
+        }
+    };
+    println!("Map connectivity: {}", connectivity);
     let station = Station::new(station_x, station_y);
 
-    let robots = vec![
-        Robot::new(station_x - 2, station_y, Direction::East, RobotRole::Explorer),
-        Robot::new(station_x - 2, station_y, Direction::East, RobotRole::Collector),
-        Robot::new(station_x + 2, station_y, Direction::North, RobotRole::Scientist),
-    ];
+    let mut simulation = Simulation::new(map, station, RoleRegistry::default());
+    simulation.add_robot(Robot::new(station_x - 2, station_y, Direction::East, RobotRole::Explorer));
+    simulation.add_robot(Robot::new(station_x - 2, station_y, Direction::East, RobotRole::Collector));
+    simulation.add_robot(Robot::new(station_x + 2, station_y, Direction::North, RobotRole::Scientist));
 
     App::new()
-        .insert_resource(SimulationData {
-            map,
-            robots,
-            station,
-            station_x,
-            station_y,
-        })
+        .insert_resource(SimulationData(simulation))
         .insert_resource(SimulationTickTimer::new())
         .insert_resource(TickCounter::new())
         .insert_resource(TickSpeedMultiplier::new())
@@ -69,6 +71,8 @@ fn main() {
         .add_systems(Update, handle_speed_buttons)
         .add_systems(Update, handle_play_pause_button)
         .add_systems(Update, update_window_title)
+        .add_systems(Update, update_robot_tooltip)
+        .add_systems(Update, update_structure_sprites)
         .run();
 }
 
@@ -80,7 +84,7 @@ fn setup_camera(mut commands: Commands) {
     let center_y = -map_height / 2.0;
 
     commands.spawn((
-        Camera2d::default(),
+        Camera2d,
         Transform::from_xyz(center_x, center_y, 0.0),
     ));
 }
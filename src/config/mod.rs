@@ -3,7 +3,161 @@ pub const MAP_HEIGHT: usize = 50; // Increased from 20
 pub const SEED: u64 = 42;
 pub const FOG_OF_WAR: bool = true; // Set to false to see the entire map
 
-// Robot creation costs
-pub const EXPLORER_COST: usize = 10;  // Energy cost to create an explorer
-pub const COLLECTOR_COST: usize = 10; // Mineral cost to create a collector
-pub const SCIENTIST_COST: usize = 10; // Science cost to create a scientist
\ No newline at end of file
+use crate::map::Cell;
+use crate::robot::RobotRole;
+use crate::station::policy::PolicyKind;
+use crate::station::tasks::Allocation;
+use crate::simulation::mission::Objective;
+use crate::map::generator::GeneratorKind;
+use crate::map::placement::{ResourceLayout, ClusterShape};
+use crate::map::Connectivity;
+
+// Map generation
+pub const MAP_GENERATOR: GeneratorKind = GeneratorKind::Perlin; // Perlin, Caves, Rooms, Islands or Maze
+pub const PERLIN_SCALE: f64 = 0.1;
+pub const PERLIN_THRESHOLD: f64 = 0.4;       // Noise above this becomes an obstacle
+pub const CAVE_FILL: f64 = 0.45;             // Initial share of rock before the caves are smoothed
+pub const CAVE_ITERATIONS: usize = 4;
+pub const ROOM_COUNT: usize = 8;
+pub const ROOM_MIN_SIZE: usize = 3;
+pub const ROOM_MAX_SIZE: usize = 8;
+pub const ISLAND_COUNT: usize = 4;
+pub const ISLAND_RADIUS: f64 = 0.2;          // Island radius, as a share of the smaller map side
+pub const MAP_CONNECTIVITY: Connectivity = Connectivity::Carve; // Ignore, Carve or Relocate resources walled off from the station
+
+// Terrain: actions spent to enter a cell of each kind; roles list the terrain they cannot cross
+pub const TERRAIN_SCALE: f64 = 0.08;
+pub const PLAIN_MOVE_COST: u32 = 1;
+pub const SAND_MOVE_COST: u32 = 2;
+pub const MUD_MOVE_COST: u32 = 3;
+pub const WATER_MOVE_COST: u32 = 2;          // Only for roles able to cross it
+pub const ROCK_MOVE_COST: u32 = 4;
+
+// Resource placement: share of the map covered, cells per cluster and cluster shape
+pub const RESOURCE_LAYOUT: &[ResourceLayout] = &[
+    ResourceLayout { cell: Cell::Energy, density: 0.05, cluster_size: 8, shape: ClusterShape::Field },
+    ResourceLayout { cell: Cell::Mineral, density: 0.05, cluster_size: 6, shape: ClusterShape::Vein },
+    ResourceLayout { cell: Cell::Science, density: 0.03, cluster_size: 1, shape: ClusterShape::Scattered },
+];
+
+// Production recipes, (resource, amount) pairs checked and deducted all at once by the station
+pub const EXPLORER_RECIPE: &[(Cell, usize)] = &[(Cell::Energy, 8), (Cell::Mineral, 2)];
+pub const COLLECTOR_RECIPE: &[(Cell, usize)] = &[(Cell::Mineral, 8), (Cell::Energy, 2)];
+pub const SCIENTIST_RECIPE: &[(Cell, usize)] = &[(Cell::Science, 6), (Cell::Energy, 4)];
+pub const HAULER_RECIPE: &[(Cell, usize)] = &[(Cell::Mineral, 10), (Cell::Energy, 5)];
+pub const BUILDER_RECIPE: &[(Cell, usize)] = &[(Cell::Mineral, 10), (Cell::Energy, 5)];
+pub const RELAY_RECIPE: &[(Cell, usize)] = &[(Cell::Energy, 10), (Cell::Mineral, 5)];
+pub const BEACON_RECIPE: &[(Cell, usize)] = &[(Cell::Mineral, 2), (Cell::Energy, 1)]; // Materials a builder carries to the site
+pub const OUTPOST_RECIPE: &[(Cell, usize)] = &[(Cell::Mineral, 30), (Cell::Energy, 20)];
+pub const UPGRADE_RECIPE: &[(Cell, usize)] = &[(Cell::Energy, 5), (Cell::Mineral, 5)]; // Per level bought, on top of research points
+
+// Robot build times, in ticks spent at the head of the production queue
+pub const EXPLORER_BUILD_TIME: u32 = 3;
+pub const COLLECTOR_BUILD_TIME: u32 = 4;
+pub const SCIENTIST_BUILD_TIME: u32 = 5;
+pub const HAULER_BUILD_TIME: u32 = 4;
+pub const BUILDER_BUILD_TIME: u32 = 5;
+pub const RELAY_BUILD_TIME: u32 = 3;
+pub const PRODUCTION_QUEUE_LENGTH: usize = 2; // Orders the station accepts ahead of time, resources reserved
+
+// Robot production
+pub const PRODUCTION_POLICY: PolicyKind = PolicyKind::DemandDriven; // How the station picks the next robot to build
+pub const FIXED_RATIO: &[(RobotRole, usize)] = &[        // Target fleet mix for PolicyKind::FixedRatio
+    (RobotRole::Explorer, 1),
+    (RobotRole::Collector, 2),
+    (RobotRole::Scientist, 1),
+];
+pub const MANUAL_QUEUE: &[RobotRole] = &[];              // Build order for PolicyKind::ManualQueue
+pub const EXPLORATION_TARGET: f32 = 0.9;     // Explorers stop being built once this share of the map is known
+pub const MAX_EXPLORERS: usize = 4;
+pub const RESOURCES_PER_COLLECTOR: usize = 10; // Known deposits per collector before another one is wanted
+pub const SCIENCE_PER_SCIENTIST: usize = 5;  // Unanalysed science deposits per scientist before another one is wanted
+pub const COLLECTORS_PER_HAULER: usize = 3;  // Collectors served by one hauler
+
+// Station task board
+pub const CLAIM_DURATION: u64 = 20;          // Ticks a resource stays reserved for a collector that stops renewing its claim
+pub const TASK_ALLOCATION: Allocation = Allocation::Greedy; // Greedy: collectors claim the nearest free resource; Auction: the station awards tasks to the lowest bids
+
+// Mission, checked every tick; the run ends on success or failure
+pub const MISSION_OBJECTIVES: &[Objective] = &[
+    Objective::Explore(0.8),
+    Objective::Deliver(Cell::Mineral, 50),
+    Objective::Deliver(Cell::Energy, 50),
+    Objective::Survive(300),
+];
+pub const MISSION_TIME_LIMIT: u64 = 2000;    // Ticks before an unfinished mission counts as failed
+
+// Station economy
+pub const STORAGE_CAPACITY: &[(Cell, usize)] = &[ // Most units of each resource the station can hold, extra deliveries are lost
+    (Cell::Energy, 150),
+    (Cell::Mineral, 150),
+    (Cell::Science, 60),
+];
+pub const UPKEEP_ENERGY_PER_ROBOT: f32 = 0.02; // Energy consumed each tick by every working robot
+pub const UPKEEP_CREDIT: f32 = 10.0;         // Unpaid upkeep tolerated before robots start shutting down
+
+// Robot battery
+pub const BATTERY_CAPACITY: u32 = 100;       // Full charge of a robot battery
+pub const MOVE_ENERGY_COST: u32 = 1;         // Battery drained by each move
+pub const SENSE_ENERGY_COST: u32 = 1;        // Battery drained by each vision scan
+pub const STATION_RECHARGE_RATE: u32 = 10;   // Battery restored per tick while docked at the station
+pub const ENERGY_UNIT_CHARGE: u32 = 25;      // Battery restored by consuming one Energy unit from the station stock
+pub const BATTERY_SAFETY_MARGIN: u32 = 6;    // Extra charge kept in reserve for the trip home
+
+// Robot cargo, expressed in weight units
+pub const COLLECTOR_CAPACITY: usize = 4;     // Cargo space of a collector
+pub const SCIENTIST_CAPACITY: usize = 1;     // Sample bay of a scientist
+pub const HAULER_CAPACITY: usize = 8;        // Cargo space of a hauler
+pub const BUILDER_CAPACITY: usize = 5;       // Room for the building materials of one beacon
+pub const ENERGY_WEIGHT: usize = 1;          // Cargo space taken by one Energy unit
+pub const MINERAL_WEIGHT: usize = 2;         // Cargo space taken by one Mineral unit
+pub const SCIENCE_WEIGHT: usize = 1;         // Cargo space taken by one Science sample
+
+// Robot speed, in points gained per tick; a robot acts once per ACTION_POINTS spent
+pub const ACTION_POINTS: u32 = 10;           // Points needed for one action (a move and its scan)
+pub const EXPLORER_SPEED: u32 = 20;          // Explorers are fast scouts, two moves per tick
+pub const COLLECTOR_SPEED: u32 = 10;
+pub const SCIENTIST_SPEED: u32 = 10;
+pub const HAULER_SPEED: u32 = 15;
+pub const BUILDER_SPEED: u32 = 10;
+pub const RELAY_SPEED: u32 = 10;
+pub const LOAD_SPEED_PENALTY: u32 = 1;       // Speed lost per unit of cargo weight carried
+
+// Action durations, in ticks
+pub const PICKUP_DURATION: u32 = 2;          // Mining a batch out of a deposit
+pub const DEPOSIT_DURATION: u32 = 2;         // Unloading cargo at the station
+pub const ANALYSIS_DURATION: u32 = 3;        // Scientist analysing a sample
+pub const BUILD_DURATION: u32 = 4;           // Builder raising a structure
+pub const ANALYSIS_RADIUS: usize = 5;        // Area surveyed around an analysed science deposit
+
+// Support roles and structures
+pub const RELAY_VISION_RANGE: usize = 5;     // Vision range of a relay
+pub const RELAY_SPACING: usize = 10;         // Minimum distance between a parked relay and the station or other relays
+pub const BEACON_SPACING: usize = 8;         // Minimum distance between a beacon and the station or other structures
+pub const BEACON_VISION_RANGE: usize = 3;    // Area a beacon keeps revealed around itself
+
+// Outposts, extra stations founded during play
+pub const MAX_OUTPOSTS: usize = 2;
+pub const OUTPOST_SPACING: usize = 15;       // Minimum distance between an outpost site and any station
+pub const OUTPOST_VISION_RANGE: usize = 2;   // Area an outpost keeps revealed around itself
+pub const SHARED_INVENTORY: bool = true;     // Outposts draw on the headquarters stock instead of keeping their own
+
+// Robot reliability
+pub const FAILURE_PROBABILITY: f64 = 0.002;  // Chance per tick that a working robot breaks down
+pub const REPAIR_MINERAL_COST: usize = 2;    // Minerals spent by the station to repair a broken robot
+
+// Research and upgrades
+pub const RESEARCH_POINTS_PER_SCIENCE: u32 = 5; // Research points earned for each Science sample deposited
+pub const UPGRADE_BASE_COST: u32 = 10;       // Research points for level 1, multiplied by the level being bought
+pub const UPGRADE_MAX_LEVEL: u32 = 3;
+pub const SENSOR_RANGE_PER_LEVEL: usize = 1; // Extra vision range per sensor level
+pub const CARRY_CAPACITY_PER_LEVEL: usize = 2; // Extra cargo space per capacity level
+pub const BATTERY_PER_LEVEL: u32 = 25;       // Extra battery capacity per battery level
+pub const SPEED_PER_LEVEL: u32 = 5;          // Extra speed points per speed level
+
+// Resource deposits
+pub const DEPOSIT_MIN_QUANTITY: usize = 1;   // Smallest number of units in a generated deposit
+pub const DEPOSIT_MAX_QUANTITY: usize = 50;  // Largest number of units in a generated deposit
+pub const ENERGY_EXTRACTION_RATE: usize = 2; // Energy units a robot extracts per tick
+pub const MINERAL_EXTRACTION_RATE: usize = 1; // Mineral units a robot extracts per tick
+pub const SCIENCE_EXTRACTION_RATE: usize = 1; // Science samples a robot extracts per tick
@@ -3,3 +3,4 @@ pub mod robot;
 pub mod station;
 pub mod resources;
 pub mod config;
+pub mod simulation;
@@ -1,88 +1,108 @@
-mod station;
-mod resources;
-mod config;
-mod map;
-use map::Map;
-use map::Cell;
-
-mod robot;
-use robot::{Robot, Direction, RobotRole};
-use std::collections::HashSet;
-use config::{MAP_WIDTH, MAP_HEIGHT, SEED};
-
-use station::Station;
+use projet_essaim::map::{Map, Cell};
+use projet_essaim::robot::{Robot, Direction, RobotRole, RoleRegistry};
+use projet_essaim::config::{MAP_WIDTH, MAP_HEIGHT, SEED};
+use projet_essaim::station::Station;
+use projet_essaim::simulation::Simulation;
 
 fn main() {
-    let seed = 42;
-    let mut map = Map::new(MAP_WIDTH, MAP_HEIGHT, SEED);
-
     // Création de la station
     let station_x = 5;
     let station_y = 3;
-    map.place_station(station_x, station_y);  // Ensure station position is empty
-    let mut station = Station::new(station_x, station_y);
+    let (map, connectivity) = match Map::try_new(MAP_WIDTH, MAP_HEIGHT, SEED, (station_x, station_y)) {
+        Ok(generated) => generated,
+        Err(error) => {
+            eprintln!("Carte invalide : {}", error);
+            std::process::exit(1);
+        }
+    };
+    println!("Connectivité de la carte : {}", connectivity);
+    let station = Station::new(station_x, station_y);
+    let mut simulation = Simulation::new(map, station, RoleRegistry::default());
 
     // Création des robots
-    let mut robots = vec![
-        Robot::new(5, 3, Direction::East, RobotRole::Explorer),
-        Robot::new(1, 3, Direction::East, RobotRole::Scientist),
-        Robot::new(1, 1, Direction::East, RobotRole::Collector),
-    ];
+    simulation.add_robot(Robot::new(5, 3, Direction::East, RobotRole::Explorer).with_name("Magellan"));
+    simulation.add_robot(Robot::new(1, 3, Direction::East, RobotRole::Scientist).with_name("Curie"));
+    simulation.add_robot(Robot::new(1, 1, Direction::East, RobotRole::Collector));
 
     println!("Carte initiale avec brouillard de guerre");
-    // map.display_with_entities(&robots, station_x, station_y);
-    // map.display_with_entities(&robots, station_x, station_y);
-    map.display_with_fog(&robots, station_x, station_y,&station);
+    simulation.map.display_with_fog(&simulation.robots, station_x, station_y, &simulation.station, &simulation.registry);
 
     // Simulation de plusieurs ticks
-    for tick in 1..=50 {
+    for _ in 1..=50 {
         println!("=====================");
-        println!("\nTick {} : exploration en cours !", tick);
+        println!("\nTick {} : exploration en cours !", simulation.tick + 1);
 
-        for (i, robot) in robots.iter_mut().enumerate() {
-            println!("Robot #{} ({:?}) en position ({}, {})", i, robot.role, robot.x, robot.y);
+        for robot in &simulation.robots {
+            println!("Robot {} en position ({}, {})", robot.label(), robot.x, robot.y);
+            if robot.is_wreck() {
+                println!("   Épave : batterie vide");
+            } else if robot.is_broken() {
+                println!("   En panne, batterie : {}", robot.battery);
+            } else if robot.is_shut_down() {
+                println!("   À l'arrêt faute d'entretien, batterie : {}", robot.battery);
+            } else {
+                println!("   Batterie : {}", robot.battery);
+            }
+            println!("   État : {} ({})", robot.state, robot.state_reason);
             if robot.role == RobotRole::Collector {
-                println!("   Ressources collectées : {:?}", robot.collected);
+                println!("   Ressources collectées : {:?} ({}/{})", robot.inventory.items(), robot.inventory.load(), robot.inventory.capacity());
                 if let Some(target) = robot.target_resource {
                     println!("   Cible actuelle : {:?}", target);
                 }
             }
-            robot.act(&mut map, station_x, station_y, &mut station);
         }
 
+        for event in simulation.step() {
+            println!("   {}", event);
+        }
+
+        let tick = simulation.tick;
+        let map = &simulation.map;
         // Affichage de la carte après chaque tick
         println!("\nCarte après Tick {} :", tick);
-        map.display_with_fog(&robots, station_x, station_y,&station);
+        map.display_with_fog(&simulation.robots, station_x, station_y, &simulation.station, &simulation.registry);
         
         // Afficher les ressources restantes
-        let mut minerals = 0;
-        let mut energy = 0;
-        for row in &map.grid {
-            for cell in row {
-                match cell {
-                    Cell::Mineral => minerals += 1,
-                    Cell::Energy => energy += 1,
-                    _ => {}
-                }
-            }
+        println!("   Minéraux restants : {}", map.remaining(Cell::Mineral));
+        println!("   Énergie restante : {}", map.remaining(Cell::Energy));
+
+        if simulation.is_finished() {
+            break;
         }
-        println!("   Minéraux restants : {}", minerals);
-        println!("   Énergie restante : {}", energy);
     }
 
     // Affichage des infos finales station + robots
     println!("\nExploration terminée !");
-    for (i, robot) in robots.iter().enumerate() {
-        println!("Robot #{} ({:?})", i, robot.role);
+    match &simulation.result {
+        Some(result) => println!("Mission : {}", result),
+        None => println!("Mission en cours ({} % de la carte accessible explorée)", (simulation.explored_share() * 100.0) as u32),
+    }
+    for robot in &simulation.robots {
+        println!("Robot {}", robot.label());
         println!("   Position finale : ({}, {})", robot.x, robot.y);
+        println!("   Batterie finale : {}", robot.battery);
+        println!("   État final : {}", robot.state);
+        println!("   Améliorations : {}", robot.upgrades);
         if robot.role == RobotRole::Collector {
-            println!("   Ressources collectées : {:?}", robot.collected);
+            println!("   Ressources collectées : {:?}", robot.inventory.items());
         }
     }
 
     // Infos fusionnées à la station
     println!("\nStation - Données fusionnées :");
+    let station = &simulation.station;
     println!("   Zones explorées (total unique) : {}", station.discovered.len());
-    println!("   Ressources collectées : {:?}", station.resources_collected);
+    println!("   Ressources collectées : {:?} (perdues faute de place : {})", station.resources_collected, station.wasted);
+    println!("   Dette d'entretien : {:.1} énergie", station.upkeep_debt);
     println!("   Robots créés au total : {}", station.robots_created);
+    println!("   Découvertes scientifiques : {}", station.scientific_discoveries);
+    println!("   Analyses reçues : {} ({} gisements cartographiés)", station.research_results.len(), station.known_deposits.len());
+    println!("   Points de recherche restants : {}", station.research_points);
+    println!("   Technologie : {}", station.technology);
+    let queue: Vec<String> = station.production_queue.iter().map(|order| order.to_string()).collect();
+    println!("   File de production : [{}]", queue.join(", "));
+    println!("   Tâches : {} ouvertes, {} réservées", station.tasks.open_tasks(), station.tasks.claims.len());
+    for outpost in &simulation.outposts {
+        println!("   Avant-poste ({}, {}) : stock {:?}", outpost.x, outpost.y, outpost.resources_collected);
+    }
 }
@@ -8,6 +8,10 @@ pub enum Cell {
 }
 
 impl Cell {
+    pub fn is_resource(&self) -> bool {
+        matches!(self, Cell::Energy | Cell::Mineral | Cell::Science)
+    }
+
     pub fn to_symbol(&self) -> &'static str {
         match self {
             Cell::Empty => " E ",
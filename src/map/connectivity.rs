@@ -0,0 +1,195 @@
+use crate::map::{Map, Cell, Terrain};
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+
+// What map generation does with resources walled off from the station
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    Ignore,   // Leave them where they are, out of reach
+    Carve,    // Cut the shortest passage through the obstacles
+    Relocate, // Move them to the nearest reachable empty cell
+}
+
+// Resource cells the station can and cannot reach, and what was done to fix the map
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectivityReport {
+    pub components: usize, // Separate open areas before any fix
+    pub reachable: usize,
+    pub unreachable: usize,
+    pub carved: usize,     // Obstacle, water or rock cells turned into passages
+    pub relocated: usize,  // Resource cells moved next to the station's area
+}
+
+impl fmt::Display for ConnectivityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} areas, {} resource cells reachable, {} unreachable", self.components, self.reachable, self.unreachable)?;
+        if self.carved > 0 {
+            write!(f, ", {} cells carved", self.carved)?;
+        }
+        if self.relocated > 0 {
+            write!(f, ", {} resources relocated", self.relocated)?;
+        }
+        Ok(())
+    }
+}
+
+// Ground that a resource-handling role cannot cross: collectors, scientists and haulers stay off water,
+// and haulers off rock as well
+const BLOCKING_TERRAIN: [Terrain; 2] = [Terrain::Water, Terrain::Rock];
+
+fn is_open(map: &Map, (x, y): (usize, usize)) -> bool {
+    map.grid[y][x] != Cell::Obstacle && !BLOCKING_TERRAIN.contains(&map.terrain_at(x, y))
+}
+
+fn neighbours(map: &Map, (x, y): (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+    [(0, 1), (1, 0), (0, -1), (-1, 0)].into_iter().filter_map(move |(dx, dy)| {
+        let (nx, ny) = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        (nx < map.width && ny < map.height).then_some((nx, ny))
+    })
+}
+
+// Cells every robot could walk to from the station
+pub fn reachable_cells(map: &Map, start: (usize, usize)) -> HashSet<(usize, usize)> {
+    let mut reachable = HashSet::new();
+    let mut queue = VecDeque::new();
+    reachable.insert(start);
+    queue.push_back(start);
+
+    while let Some(position) = queue.pop_front() {
+        for (nx, ny) in neighbours(map, position) {
+            if is_open(map, (nx, ny)) && reachable.insert((nx, ny)) {
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    reachable
+}
+
+// Open areas of the map, each a set of cells connected without crossing an obstacle, water or rock
+pub fn components(map: &Map) -> Vec<HashSet<(usize, usize)>> {
+    let mut seen = HashSet::new();
+    let mut areas = Vec::new();
+    for y in 0..map.height {
+        for x in 0..map.width {
+            if is_open(map, (x, y)) && !seen.contains(&(x, y)) {
+                let area = reachable_cells(map, (x, y));
+                seen.extend(area.iter().copied());
+                areas.push(area);
+            }
+        }
+    }
+    areas
+}
+
+impl Map {
+    // Makes sure robots leaving the station can get to the resources, as configured
+    pub fn ensure_connectivity(&mut self, station: (usize, usize), mode: Connectivity) -> ConnectivityReport {
+        let mut report = ConnectivityReport { components: components(self).len(), ..Default::default() };
+        let mut reachable = reachable_cells(self, station);
+
+        while let Some(resource) = self.first_unreachable_resource(&reachable) {
+            match mode {
+                Connectivity::Ignore => break,
+                Connectivity::Carve => {
+                    report.carved += self.carve_passage(resource, &reachable);
+                    reachable = reachable_cells(self, station);
+                }
+                Connectivity::Relocate => {
+                    if !self.relocate_resource(resource, &reachable, station) {
+                        break;
+                    }
+                    report.relocated += 1;
+                }
+            }
+        }
+
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if cell.is_resource() {
+                    if reachable.contains(&(x, y)) {
+                        report.reachable += 1;
+                    } else {
+                        report.unreachable += 1;
+                    }
+                }
+            }
+        }
+        report
+    }
+
+    fn first_unreachable_resource(&self, reachable: &HashSet<(usize, usize)>) -> Option<(usize, usize)> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .find(|&(x, y)| self.grid[y][x].is_resource() && !reachable.contains(&(x, y)))
+    }
+
+    // Opens the route to the reachable area that crosses the fewest obstacles, water or rock cells, returning
+    // how many were cleared; crossed ground becomes plain
+    fn carve_passage(&mut self, from: (usize, usize), reachable: &HashSet<(usize, usize)>) -> usize {
+        let mut cost = vec![vec![usize::MAX; self.width]; self.height];
+        let mut came_from = vec![vec![None; self.width]; self.height];
+        let mut queue = VecDeque::new();
+        cost[from.1][from.0] = 0;
+        queue.push_back(from);
+
+        // 0-1 search: open cells are free, blocked ones cost one
+        let mut end = None;
+        while let Some(position) = queue.pop_front() {
+            if reachable.contains(&position) {
+                end = Some(position);
+                break;
+            }
+            for (nx, ny) in neighbours(self, position) {
+                let step = usize::from(!is_open(self, (nx, ny)));
+                let next = cost[position.1][position.0] + step;
+                if next < cost[ny][nx] {
+                    cost[ny][nx] = next;
+                    came_from[ny][nx] = Some(position);
+                    if step == 0 {
+                        queue.push_front((nx, ny));
+                    } else {
+                        queue.push_back((nx, ny));
+                    }
+                }
+            }
+        }
+
+        let mut carved = 0;
+        let mut current = end.and_then(|(x, y)| came_from[y][x]);
+        while let Some((x, y)) = current {
+            if !is_open(self, (x, y)) {
+                if self.grid[y][x] == Cell::Obstacle {
+                    self.grid[y][x] = Cell::Empty;
+                }
+                self.terrain[y][x] = Terrain::Plain;
+                carved += 1;
+            }
+            current = came_from[y][x];
+        }
+        carved
+    }
+
+    // Moves a resource and its deposit to the nearest empty cell of the reachable area
+    fn relocate_resource(&mut self, from: (usize, usize), reachable: &HashSet<(usize, usize)>, station: (usize, usize)) -> bool {
+        let mut visited = HashSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(position) = queue.pop_front() {
+            let (x, y) = position;
+            if reachable.contains(&position) && position != station
+                && self.grid[y][x] == Cell::Empty && !self.structures.contains_key(&position) {
+                self.grid[y][x] = self.grid[from.1][from.0];
+                self.grid[from.1][from.0] = Cell::Empty;
+                if let Some(deposit) = self.deposits.remove(&from) {
+                    self.deposits.insert(position, deposit);
+                }
+                return true;
+            }
+            for next in neighbours(self, position) {
+                if visited.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        false
+    }
+}
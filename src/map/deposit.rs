@@ -0,0 +1,33 @@
+use crate::map::Cell;
+use crate::config::{ENERGY_EXTRACTION_RATE, MINERAL_EXTRACTION_RATE, SCIENCE_EXTRACTION_RATE};
+
+// Units one robot extracts from a deposit of this resource per tick
+pub fn extraction_rate(cell: Cell) -> usize {
+    match cell {
+        Cell::Energy => ENERGY_EXTRACTION_RATE,
+        Cell::Mineral => MINERAL_EXTRACTION_RATE,
+        Cell::Science => SCIENCE_EXTRACTION_RATE,
+        Cell::Empty | Cell::Obstacle => 0,
+    }
+}
+
+// Amount of resource left in a map cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deposit {
+    pub quantity: usize,
+    pub initial: usize,
+}
+
+impl Deposit {
+    pub fn new(quantity: usize) -> Self {
+        Self { quantity, initial: quantity }
+    }
+
+    // Share of the deposit already mined, from 0.0 (untouched) to 1.0 (exhausted)
+    pub fn depletion(&self) -> f32 {
+        if self.initial == 0 {
+            return 1.0;
+        }
+        1.0 - self.quantity as f32 / self.initial as f32
+    }
+}
@@ -0,0 +1,26 @@
+use crate::map::Cell;
+use std::fmt;
+
+// Why a map could not be generated, see `Map::try_new`
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapError {
+    EmptyDimensions { width: usize, height: usize },
+    InvalidDensity { cell: Cell, density: f32 },       // Not a share between 0 and 1
+    DensityOverflow { total: f32 },                    // Resources would cover more than the whole map
+    StationOutOfBounds { x: usize, y: usize, width: usize, height: usize },
+    NoRoomForResources { wanted: usize, available: usize }, // Fewer empty cells left by the generator than resources requested
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::EmptyDimensions { width, height } => write!(f, "a {}x{} map has no cells", width, height),
+            MapError::InvalidDensity { cell, density } => write!(f, "{:?} density {} is not between 0 and 1", cell, density),
+            MapError::DensityOverflow { total } => write!(f, "resource densities add up to {}, more than the whole map", total),
+            MapError::StationOutOfBounds { x, y, width, height } => write!(f, "station at ({}, {}) is outside the {}x{} map", x, y, width, height),
+            MapError::NoRoomForResources { wanted, available } => write!(f, "{} resource cells requested but only {} empty cells", wanted, available),
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
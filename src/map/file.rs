@@ -0,0 +1,307 @@
+use crate::map::{Map, Cell, Terrain, Deposit, Structure};
+use crate::robot::{Robot, Direction, RoleRegistry};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
+
+// Text map format: one line per row, one symbol per cell, spaces ignored so that `Map::display` output loads back.
+// Cells use the `Cell::to_symbol` letters (E, O, P, M, S), empty ground can be written with its terrain
+// (. sand, , mud, ~ water, ^ rock), H marks the station and a role's console letter marks a robot spawn.
+// An optional section after a `---` line keeps what the grid cannot show, one entry per line:
+// `deposit x y quantity initial`, `terrain x y symbol` for ground hidden under another symbol, and
+// `structure x y beacon|outpost`. Resource cells without a deposit entry start half full.
+const STATION_SYMBOL: char = 'H';
+const DETAILS_SEPARATOR: &str = "---";
+
+// A map read from text, with the station and robot spawns it marks
+#[derive(Debug, Clone)]
+pub struct MapFile {
+    pub map: Map,
+    pub station: Option<(usize, usize)>,
+    pub spawns: Vec<(usize, usize, char)>, // Position and role letter of each robot marker
+}
+
+#[derive(Debug)]
+pub enum MapFileError {
+    Io(std::io::Error),
+    Empty,
+    RaggedRow { line: usize, expected: usize, found: usize },
+    UnknownSymbol { line: usize, column: usize, symbol: char },
+    SeveralStations { first: (usize, usize), second: (usize, usize) },
+    InvalidEntry { line: usize },
+}
+
+impl fmt::Display for MapFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapFileError::Io(error) => write!(f, "cannot read or write the map file: {}", error),
+            MapFileError::Empty => write!(f, "the map file has no cells"),
+            MapFileError::RaggedRow { line, expected, found } => write!(f, "line {} has {} cells, expected {}", line, found, expected),
+            MapFileError::UnknownSymbol { line, column, symbol } => write!(f, "unknown symbol '{}' at line {}, cell {}", symbol, line, column),
+            MapFileError::SeveralStations { first, second } => write!(f, "two stations marked, at {:?} and {:?}", first, second),
+            MapFileError::InvalidEntry { line } => write!(f, "line {} is not a valid deposit, terrain or structure entry", line),
+        }
+    }
+}
+
+impl std::error::Error for MapFileError {}
+
+impl From<std::io::Error> for MapFileError {
+    fn from(error: std::io::Error) -> Self {
+        MapFileError::Io(error)
+    }
+}
+
+fn cell_letter(cell: Cell) -> char {
+    cell.to_symbol().trim().chars().next().unwrap_or('E')
+}
+
+fn terrain_letter(terrain: Terrain) -> Option<char> {
+    match terrain {
+        Terrain::Plain => None,
+        Terrain::Sand => Some('.'),
+        Terrain::Mud => Some(','),
+        Terrain::Water => Some('~'),
+        Terrain::Rock => Some('^'),
+    }
+}
+
+fn structure_name(structure: Structure) -> &'static str {
+    match structure {
+        Structure::Beacon => "beacon",
+        Structure::Outpost => "outpost",
+    }
+}
+
+// Cell and terrain under a symbol; station and robot markers stand on empty plain ground
+fn parse_symbol(symbol: char) -> Option<(Cell, Terrain)> {
+    [Cell::Empty, Cell::Obstacle, Cell::Energy, Cell::Mineral, Cell::Science].into_iter()
+        .find(|&cell| cell_letter(cell) == symbol)
+        .map(|cell| (cell, Terrain::Plain))
+        .or_else(|| Terrain::ALL.into_iter().find(|&terrain| terrain_letter(terrain) == Some(symbol)).map(|terrain| (Cell::Empty, terrain)))
+}
+
+impl MapFile {
+    pub fn parse(text: &str, registry: &RoleRegistry) -> Result<Self, MapFileError> {
+        let role_letters: Vec<char> = registry.roles().map(|(_, definition)| definition.symbol).collect();
+        let mut grid = Vec::new();
+        let mut terrain = Vec::new();
+        let mut station = None;
+        let mut spawns = Vec::new();
+        let mut details = Vec::new();
+
+        let mut lines = text.lines().enumerate();
+        for (index, line) in lines.by_ref() {
+            if line.trim() == DETAILS_SEPARATOR {
+                details.extend(lines.by_ref());
+                break;
+            }
+            let symbols: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+            if symbols.is_empty() {
+                continue;
+            }
+            if let Some(first) = grid.first().map(Vec::len).filter(|&width| width != symbols.len()) {
+                return Err(MapFileError::RaggedRow { line: index + 1, expected: first, found: symbols.len() });
+            }
+
+            let y = grid.len();
+            let mut row = Vec::with_capacity(symbols.len());
+            let mut ground = Vec::with_capacity(symbols.len());
+            for (x, &symbol) in symbols.iter().enumerate() {
+                let (cell, under) = if symbol == STATION_SYMBOL {
+                    if let Some(first) = station {
+                        return Err(MapFileError::SeveralStations { first, second: (x, y) });
+                    }
+                    station = Some((x, y));
+                    (Cell::Empty, Terrain::Plain)
+                } else if role_letters.contains(&symbol) {
+                    spawns.push((x, y, symbol));
+                    (Cell::Empty, Terrain::Plain)
+                } else {
+                    parse_symbol(symbol).ok_or(MapFileError::UnknownSymbol { line: index + 1, column: x + 1, symbol })?
+                };
+                row.push(cell);
+                ground.push(under);
+            }
+            grid.push(row);
+            terrain.push(ground);
+        }
+
+        if grid.is_empty() {
+            return Err(MapFileError::Empty);
+        }
+
+        let mut map = Map::from_cells(grid);
+        map.terrain = terrain;
+        for (index, line) in details {
+            if !line.trim().is_empty() && !map.apply_entry(line) {
+                return Err(MapFileError::InvalidEntry { line: index + 1 });
+            }
+        }
+        Ok(Self { map, station, spawns })
+    }
+
+    pub fn load(path: impl AsRef<Path>, registry: &RoleRegistry) -> Result<Self, MapFileError> {
+        Self::parse(&fs::read_to_string(path)?, registry)
+    }
+
+    // Robots for the spawn markers, facing east; letters of unregistered roles are skipped
+    pub fn robots(&self, registry: &RoleRegistry) -> Vec<Robot> {
+        self.spawns.iter()
+            .filter_map(|&(x, y, symbol)| {
+                let (role, _) = registry.roles().find(|(_, definition)| definition.symbol == symbol)?;
+                Some(Robot::new(x, y, Direction::East, role))
+            })
+            .collect()
+    }
+}
+
+impl Map {
+    // Symbol of a cell in the text format, without station or robots
+    fn text_symbol(&self, x: usize, y: usize) -> char {
+        match self.grid[y][x] {
+            Cell::Empty => terrain_letter(self.terrain_at(x, y)).unwrap_or_else(|| cell_letter(Cell::Empty)),
+            cell => cell_letter(cell),
+        }
+    }
+
+    // Reads one line of the details section, false if it does not fit this map
+    fn apply_entry(&mut self, line: &str) -> bool {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [kind, x, y, rest @ ..] = fields.as_slice() else {
+            return false;
+        };
+        let (Ok(x), Ok(y)) = (x.parse::<usize>(), y.parse::<usize>()) else {
+            return false;
+        };
+        if x >= self.width || y >= self.height {
+            return false;
+        }
+        match (*kind, rest) {
+            ("deposit", [quantity, initial]) if self.grid[y][x].is_resource() => {
+                // An empty deposit, or one holding more than it started with, cannot be mined
+                let (Ok(quantity), Ok(initial)) = (quantity.parse(), initial.parse()) else {
+                    return false;
+                };
+                if quantity == 0 || quantity > initial {
+                    return false;
+                }
+                self.deposits.insert((x, y), Deposit { quantity, initial });
+            }
+            ("terrain", [symbol]) => {
+                let Some((Cell::Empty, terrain)) = symbol.parse().ok().and_then(parse_symbol) else {
+                    return false;
+                };
+                self.terrain[y][x] = terrain;
+            }
+            ("structure", [name]) => {
+                let Some(structure) = [Structure::Beacon, Structure::Outpost].into_iter().find(|&structure| structure_name(structure) == *name) else {
+                    return false;
+                };
+                return self.build(x, y, structure);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    // Details section for everything the grid symbols leave out; `shown` tells whether the ground at a
+    // position was written in the grid
+    fn details_text(&self, shown: impl Fn(usize, usize) -> bool) -> String {
+        let mut lines = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if let Some(deposit) = self.deposits.get(&(x, y)) {
+                    lines.push(format!("deposit {} {} {} {}", x, y, deposit.quantity, deposit.initial));
+                }
+                if let Some(letter) = terrain_letter(self.terrain_at(x, y)).filter(|_| !shown(x, y)) {
+                    lines.push(format!("terrain {} {} {}", x, y, letter));
+                }
+                if let Some(&structure) = self.structures.get(&(x, y)) {
+                    lines.push(format!("structure {} {} {}", x, y, structure_name(structure)));
+                }
+            }
+        }
+        if lines.is_empty() {
+            return String::new();
+        }
+        format!("{}\n{}\n", DETAILS_SEPARATOR, lines.join("\n"))
+    }
+
+    pub fn to_text(&self) -> String {
+        self.to_text_with_entities(None, &[], &RoleRegistry::new())
+    }
+
+    // Text snapshot of a map mid-run; wrecks, broken and shut-down robots are left out, and so are markers
+    // on non-empty cells, which would hide them. Loading it back gives the same grid, terrain, deposits
+    // and structures
+    pub fn to_text_with_entities(&self, station: Option<(usize, usize)>, robots: &[Robot], registry: &RoleRegistry) -> String {
+        let marker = |x: usize, y: usize| {
+            if self.grid[y][x] != Cell::Empty {
+                return None;
+            }
+            if station == Some((x, y)) {
+                return Some(STATION_SYMBOL);
+            }
+            robots.iter()
+                .filter(|robot| robot.x == x && robot.y == y && !robot.is_out_of_service())
+                .find_map(|robot| registry.get(robot.role).map(|definition| definition.symbol))
+        };
+        let mut text = String::with_capacity((self.width * 2 + 1) * self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if x > 0 {
+                    text.push(' ');
+                }
+                text.push(marker(x, y).unwrap_or_else(|| self.text_symbol(x, y)));
+            }
+            text.push('\n');
+        }
+        text.push_str(&self.details_text(|x, y| self.grid[y][x] == Cell::Empty && marker(x, y).is_none()));
+        text
+    }
+
+    pub fn save_text(&self, path: impl AsRef<Path>) -> Result<(), MapFileError> {
+        fs::write(path, self.to_text())?;
+        Ok(())
+    }
+
+    pub fn save_text_with_entities(&self, path: impl AsRef<Path>, station: Option<(usize, usize)>, robots: &[Robot], registry: &RoleRegistry) -> Result<(), MapFileError> {
+        fs::write(path, self.to_text_with_entities(station, robots, registry))?;
+        Ok(())
+    }
+
+    // RGB colour of a cell in exported images, matching the interface
+    fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        let [r, g, b] = match self.grid[y][x] {
+            Cell::Empty => self.terrain_at(x, y).color(),
+            Cell::Obstacle => [0.3, 0.3, 0.3],
+            Cell::Energy => [1.0, 0.8, 0.0],
+            Cell::Mineral => [0.6, 0.3, 0.8],
+            Cell::Science => [0.0, 0.8, 1.0],
+        };
+        [(r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8]
+    }
+
+    // Writes the map as a PNG with `scale` pixels per cell, the station drawn in dark red
+    pub fn save_png(&self, path: impl AsRef<Path>, scale: u32, station: Option<(usize, usize)>) -> Result<(), MapFileError> {
+        let scale = scale.max(1) as usize;
+        let (width, height) = (self.width * scale, self.height * scale);
+        let mut data = Vec::with_capacity(width * height * 3);
+        for py in 0..height {
+            for px in 0..width {
+                let (x, y) = (px / scale, py / scale);
+                let pixel = if station == Some((x, y)) { [153, 0, 0] } else { self.pixel(x, y) };
+                data.extend_from_slice(&pixel);
+            }
+        }
+
+        let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|error| MapFileError::Io(error.into()))?;
+        writer.write_image_data(&data).map_err(|error| MapFileError::Io(error.into()))?;
+        Ok(())
+    }
+}
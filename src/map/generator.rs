@@ -0,0 +1,194 @@
+use crate::map::Cell;
+use crate::config::{
+    MAP_GENERATOR, PERLIN_SCALE, PERLIN_THRESHOLD, CAVE_FILL, CAVE_ITERATIONS,
+    ROOM_COUNT, ROOM_MIN_SIZE, ROOM_MAX_SIZE, ISLAND_COUNT, ISLAND_RADIUS,
+};
+use noise::{NoiseFn, Perlin};
+use rand::{SeedableRng, rngs::StdRng, Rng};
+
+// Lays out the obstacles of a map; resources are scattered afterwards on the empty cells
+pub trait MapGenerator {
+    fn generate(&self, width: usize, height: usize, seed: u64) -> Vec<Vec<Cell>>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeneratorKind {
+    Perlin,
+    Caves,
+    Rooms,
+    Islands,
+    Maze,
+}
+
+// Builds the generator selected in the configuration
+pub fn generator_from_config() -> Box<dyn MapGenerator> {
+    match MAP_GENERATOR {
+        GeneratorKind::Perlin => Box::new(PerlinGenerator { scale: PERLIN_SCALE, threshold: PERLIN_THRESHOLD }),
+        GeneratorKind::Caves => Box::new(CaveGenerator { fill: CAVE_FILL, iterations: CAVE_ITERATIONS }),
+        GeneratorKind::Rooms => Box::new(RoomsGenerator { rooms: ROOM_COUNT, min_size: ROOM_MIN_SIZE, max_size: ROOM_MAX_SIZE }),
+        GeneratorKind::Islands => Box::new(IslandsGenerator { islands: ISLAND_COUNT, radius: ISLAND_RADIUS }),
+        GeneratorKind::Maze => Box::new(MazeGenerator),
+    }
+}
+
+// Original behaviour: obstacles wherever a single Perlin pass rises above a threshold
+pub struct PerlinGenerator {
+    pub scale: f64,
+    pub threshold: f64,
+}
+
+impl MapGenerator for PerlinGenerator {
+    fn generate(&self, width: usize, height: usize, seed: u64) -> Vec<Vec<Cell>> {
+        let perlin = Perlin::new(seed as u32);
+        let mut grid = vec![vec![Cell::Empty; width]; height];
+        for (y, row) in grid.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                let noise_val = perlin.get([x as f64 * self.scale, y as f64 * self.scale, seed as f64]);
+                if noise_val > self.threshold {
+                    *cell = Cell::Obstacle;
+                }
+            }
+        }
+        grid
+    }
+}
+
+// Cellular automaton: random rock smoothed into winding caves
+pub struct CaveGenerator {
+    pub fill: f64,        // Initial share of rock
+    pub iterations: usize,
+}
+
+impl MapGenerator for CaveGenerator {
+    fn generate(&self, width: usize, height: usize, seed: u64) -> Vec<Vec<Cell>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut grid: Vec<Vec<Cell>> = (0..height)
+            .map(|_| (0..width).map(|_| if rng.gen_bool(self.fill) { Cell::Obstacle } else { Cell::Empty }).collect())
+            .collect();
+
+        for _ in 0..self.iterations {
+            let previous = grid.clone();
+            for (y, row) in grid.iter_mut().enumerate() {
+                for (x, cell) in row.iter_mut().enumerate() {
+                    // Cells off the map count as rock, which closes the caves at the edges
+                    let mut walls = 0;
+                    for dy in -1..=1isize {
+                        for dx in -1..=1isize {
+                            if (dx, dy) == (0, 0) {
+                                continue;
+                            }
+                            let neighbour = x.checked_add_signed(dx).zip(y.checked_add_signed(dy))
+                                .and_then(|(nx, ny)| previous.get(ny).and_then(|row| row.get(nx)));
+                            if neighbour.is_none_or(|&cell| cell == Cell::Obstacle) {
+                                walls += 1;
+                            }
+                        }
+                    }
+                    *cell = match walls {
+                        5.. => Cell::Obstacle,
+                        0..=3 => Cell::Empty,
+                        _ => previous[y][x],
+                    };
+                }
+            }
+        }
+        grid
+    }
+}
+
+// Solid rock with rectangular rooms, each joined to the previous one by an L-shaped corridor
+pub struct RoomsGenerator {
+    pub rooms: usize,
+    pub min_size: usize,
+    pub max_size: usize,
+}
+
+impl MapGenerator for RoomsGenerator {
+    fn generate(&self, width: usize, height: usize, seed: u64) -> Vec<Vec<Cell>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut grid = vec![vec![Cell::Obstacle; width]; height];
+        let mut previous: Option<(usize, usize)> = None;
+
+        for _ in 0..self.rooms {
+            let room_width = rng.gen_range(self.min_size..=self.max_size).min(width);
+            let room_height = rng.gen_range(self.min_size..=self.max_size).min(height);
+            let left = rng.gen_range(0..=width - room_width);
+            let top = rng.gen_range(0..=height - room_height);
+            for row in &mut grid[top..top + room_height] {
+                row[left..left + room_width].fill(Cell::Empty);
+            }
+
+            let centre = (left + room_width / 2, top + room_height / 2);
+            if let Some((px, py)) = previous {
+                grid[py][px.min(centre.0)..=px.max(centre.0)].fill(Cell::Empty);
+                for row in &mut grid[py.min(centre.1)..=py.max(centre.1)] {
+                    row[centre.0] = Cell::Empty;
+                }
+            }
+            previous = Some(centre);
+        }
+        grid
+    }
+}
+
+// Open ground around a few island centres, with impassable water in between
+pub struct IslandsGenerator {
+    pub islands: usize,
+    pub radius: f64, // Island radius, as a share of the smaller map side
+}
+
+impl MapGenerator for IslandsGenerator {
+    fn generate(&self, width: usize, height: usize, seed: u64) -> Vec<Vec<Cell>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let perlin = Perlin::new(seed as u32);
+        let centres: Vec<(f64, f64)> = (0..self.islands)
+            .map(|_| (rng.gen_range(0.0..width as f64), rng.gen_range(0.0..height as f64)))
+            .collect();
+        let radius = self.radius * width.min(height) as f64;
+
+        let mut grid = vec![vec![Cell::Obstacle; width]; height];
+        for (y, row) in grid.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                // Noise roughens the coastline
+                let coast = radius * (1.0 + 0.4 * perlin.get([x as f64 * 0.15, y as f64 * 0.15, seed as f64]));
+                let on_island = centres.iter()
+                    .any(|&(cx, cy)| ((x as f64 - cx).powi(2) + (y as f64 - cy).powi(2)).sqrt() <= coast);
+                if on_island {
+                    *cell = Cell::Empty;
+                }
+            }
+        }
+        grid
+    }
+}
+
+// Perfect maze carved by a randomised depth-first search, corridors on odd coordinates
+pub struct MazeGenerator;
+
+impl MapGenerator for MazeGenerator {
+    fn generate(&self, width: usize, height: usize, seed: u64) -> Vec<Vec<Cell>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut grid = vec![vec![Cell::Obstacle; width]; height];
+        if width < 2 || height < 2 {
+            return grid;
+        }
+
+        let mut stack: Vec<(usize, usize)> = vec![(1, 1)];
+        grid[1][1] = Cell::Empty;
+        while let Some(&(x, y)) = stack.last() {
+            let unvisited: Vec<(usize, usize)> = [(0, 2), (2, 0), (0, -2), (-2, 0)].into_iter()
+                .filter_map(|(dx, dy): (isize, isize)| Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?)))
+                .filter(|&(nx, ny)| nx < width && ny < height && grid[ny][nx] == Cell::Obstacle)
+                .collect();
+            if unvisited.is_empty() {
+                stack.pop();
+                continue;
+            }
+            let (nx, ny) = unvisited[rng.gen_range(0..unvisited.len())];
+            grid[(y + ny) / 2][(x + nx) / 2] = Cell::Empty;
+            grid[ny][nx] = Cell::Empty;
+            stack.push((nx, ny));
+        }
+        grid
+    }
+}
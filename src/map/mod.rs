@@ -1,10 +1,26 @@
 pub mod cell;
+pub mod connectivity;
+pub mod deposit;
+pub mod error;
+pub mod file;
+pub mod generator;
+pub mod placement;
+pub mod structure;
+pub mod terrain;
 pub use cell::Cell;
-use noise::{NoiseFn, Perlin};
+pub use deposit::{Deposit, extraction_rate};
+pub use structure::Structure;
+pub use terrain::Terrain;
+pub use connectivity::{Connectivity, ConnectivityReport};
+pub use file::{MapFile, MapFileError};
+pub use error::MapError;
+pub use generator::{MapGenerator, GeneratorKind, generator_from_config};
 use rand::{SeedableRng, rngs::StdRng, Rng};
-use crate::robot::Robot;
+use crate::robot::{Robot, RoleRegistry};
 use crate::station::Station;
-use std::collections::HashSet;
+use crate::config::{DEPOSIT_MIN_QUANTITY, DEPOSIT_MAX_QUANTITY, RESOURCE_LAYOUT, MAP_CONNECTIVITY};
+use placement::ResourceLayout;
+use std::collections::{HashMap, HashSet};
 
 
 #[derive(Debug, Clone)]
@@ -12,57 +28,158 @@ pub struct Map {
     pub width: usize,
     pub height: usize,
     pub grid: Vec<Vec<Cell>>,
+    pub terrain: Vec<Vec<Terrain>>, // Ground under each cell, setting its movement cost
+    pub deposits: HashMap<(usize, usize), Deposit>, // Units left in each resource cell
+    pub structures: HashMap<(usize, usize), Structure>,
 }
 
 impl Map {
+    // Map laid out by the generator selected in the configuration
     pub fn new(width: usize, height: usize, seed: u64) -> Self {
-        let perlin = Perlin::new(seed as u32);
-        let mut grid = vec![vec![Cell::Empty; width]; height];
-        let scale = 0.1;
-
-        for y in 0..height {
-            for x in 0..width {
-                let noise_val = perlin.get([x as f64 * scale, y as f64 * scale, seed as f64]);
-                if noise_val > 0.4 {
-                    grid[y][x] = Cell::Obstacle;
+        Self::generate(width, height, seed, generator_from_config().as_ref())
+    }
+
+    // Checked version of `Map::new`: validates the dimensions, the resource densities and the station
+    // position, clears the station cell and links walled-off resources as set in the configuration
+    pub fn try_new(width: usize, height: usize, seed: u64, station: (usize, usize)) -> Result<(Self, ConnectivityReport), MapError> {
+        Self::try_generate(width, height, seed, station, generator_from_config().as_ref(), RESOURCE_LAYOUT, MAP_CONNECTIVITY)
+    }
+
+    pub fn try_generate(width: usize, height: usize, seed: u64, station: (usize, usize), generator: &dyn MapGenerator, layouts: &[ResourceLayout], connectivity: Connectivity) -> Result<(Self, ConnectivityReport), MapError> {
+        if width == 0 || height == 0 {
+            return Err(MapError::EmptyDimensions { width, height });
+        }
+        if station.0 >= width || station.1 >= height {
+            return Err(MapError::StationOutOfBounds { x: station.0, y: station.1, width, height });
+        }
+        if let Some(layout) = layouts.iter().find(|layout| !(0.0..=1.0).contains(&layout.density)) {
+            return Err(MapError::InvalidDensity { cell: layout.cell, density: layout.density });
+        }
+        let total: f32 = layouts.iter().map(|layout| layout.density).sum();
+        if total > 1.0 {
+            return Err(MapError::DensityOverflow { total });
+        }
+
+        // The station cell is walled off while resources are placed so none lands under it
+        let mut grid = generator.generate(width, height, seed);
+        grid[station.1][station.0] = Cell::Obstacle;
+        let wanted: usize = layouts.iter().map(|layout| ((width * height) as f32 * layout.density) as usize).sum();
+        let available = grid.iter().flatten().filter(|cell| **cell == Cell::Empty).count();
+        if wanted > available {
+            return Err(MapError::NoRoomForResources { wanted, available });
+        }
+
+        let mut map = Self::populate(grid, width, height, seed, layouts);
+        map.place_station(station.0, station.1);
+        let report = map.ensure_connectivity(station, connectivity);
+        Ok((map, report))
+    }
+
+    pub fn generate(width: usize, height: usize, seed: u64, generator: &dyn MapGenerator) -> Self {
+        Self::populate(generator.generate(width, height, seed), width, height, seed, RESOURCE_LAYOUT)
+    }
+
+    // Resources, deposits and terrain laid over a generated grid
+    fn populate(mut grid: Vec<Vec<Cell>>, width: usize, height: usize, seed: u64, layouts: &[ResourceLayout]) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        placement::place_resources(&mut grid, layouts, &mut rng);
+
+        let mut deposits = HashMap::new();
+        for (y, row) in grid.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if cell.is_resource() {
+                    let quantity = rng.gen_range(DEPOSIT_MIN_QUANTITY..=DEPOSIT_MAX_QUANTITY);
+                    deposits.insert((x, y), Deposit::new(quantity));
                 }
             }
         }
 
-        let mut rng = StdRng::seed_from_u64(seed);
+        let terrain = terrain::generate_terrain(width, height, seed);
 
-        let map_area = width * height;
-        let energy_count = (map_area as f32 * 0.05) as usize;
-        let mineral_count = (map_area as f32 * 0.05) as usize;
-        let science_count = (map_area as f32 * 0.03) as usize;
+        Self { width, height, grid, terrain, deposits, structures: HashMap::new() }
+    }
 
-        Map::place_random(&mut grid, Cell::Energy, energy_count, &mut rng);
-        Map::place_random(&mut grid, Cell::Mineral, mineral_count, &mut rng);
-        Map::place_random(&mut grid, Cell::Science, science_count, &mut rng);
+    // Map taken as drawn, on plain ground, every resource cell holding a half-full deposit
+    pub fn from_cells(grid: Vec<Vec<Cell>>) -> Self {
+        let (width, height) = (grid.first().map_or(0, Vec::len), grid.len());
+        let quantity = (DEPOSIT_MIN_QUANTITY + DEPOSIT_MAX_QUANTITY) / 2;
+        let deposits = grid.iter().enumerate()
+            .flat_map(|(y, row)| row.iter().enumerate().filter(|(_, cell)| cell.is_resource()).map(move |(x, _)| ((x, y), Deposit::new(quantity))))
+            .collect();
+        let terrain = vec![vec![Terrain::Plain; width]; height];
+        Self { width, height, grid, terrain, deposits, structures: HashMap::new() }
+    }
 
-        Self { width, height, grid }
+    pub fn terrain_at(&self, x: usize, y: usize) -> Terrain {
+        self.terrain.get(y).and_then(|row| row.get(x)).copied().unwrap_or_default()
     }
 
-    pub fn place_station(&mut self, x: usize, y: usize) {
-        if x < self.width && y < self.height {
-            self.grid[y][x] = Cell::Empty;
-        }
+    // Actions needed to step onto a position
+    pub fn move_cost(&self, x: usize, y: usize) -> u32 {
+        self.terrain_at(x, y).move_cost()
     }
 
-    fn place_random(grid: &mut Vec<Vec<Cell>>, kind: Cell, count: usize, rng: &mut StdRng) {
-        let height = grid.len();
-        let width = grid[0].len();
-        let mut placed = 0;
+    // Console symbol of a cell, showing the ground under empty cells
+    fn cell_symbol(&self, x: usize, y: usize) -> String {
+        match self.grid[y][x] {
+            Cell::Empty => self.terrain_at(x, y).to_colored_symbol().to_string(),
+            cell => cell.to_colored_symbol().to_string(),
+        }
+    }
 
-        while placed < count {
-            let x = rng.gen_range(0..width);
-            let y = rng.gen_range(0..height);
+    // Units left at a position; resource cells without a recorded deposit hold a single unit
+    pub fn quantity(&self, x: usize, y: usize) -> usize {
+        if !self.grid[y][x].is_resource() {
+            return 0;
+        }
+        self.deposits.get(&(x, y)).map_or(1, |deposit| deposit.quantity)
+    }
 
-            if grid[y][x] == Cell::Empty {
-                grid[y][x] = kind;
-                placed += 1;
+    // Total units of a resource left on the map
+    pub fn remaining(&self, kind: Cell) -> usize {
+        let mut total = 0;
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if *cell == kind {
+                    total += self.quantity(x, y);
+                }
             }
         }
+        total
+    }
+
+    // Takes one unit from the deposit at a position, clearing the cell once it runs dry
+    pub fn extract(&mut self, x: usize, y: usize) -> Option<Cell> {
+        let cell = self.grid[y][x];
+        if !cell.is_resource() {
+            return None;
+        }
+
+        let left = self.quantity(x, y) - 1;
+        if left == 0 {
+            self.grid[y][x] = Cell::Empty;
+            self.deposits.remove(&(x, y));
+        } else if let Some(deposit) = self.deposits.get_mut(&(x, y)) {
+            deposit.quantity = left;
+        }
+        Some(cell)
+    }
+
+    // Structures go on empty cells only, one per cell
+    pub fn build(&mut self, x: usize, y: usize, structure: Structure) -> bool {
+        if self.grid[y][x] != Cell::Empty || self.structures.contains_key(&(x, y)) {
+            return false;
+        }
+        self.structures.insert((x, y), structure);
+        true
+    }
+
+    pub fn place_station(&mut self, x: usize, y: usize) {
+        if x < self.width && y < self.height {
+            self.grid[y][x] = Cell::Empty;
+            self.terrain[y][x] = Terrain::Plain;
+            self.deposits.remove(&(x, y));
+        }
     }
 
     pub fn display(&self) {
@@ -87,16 +204,28 @@ impl Map {
         }
     }
 
-    pub fn display_with_entities(&self, robots: &[Robot], station_x: usize, station_y: usize) {
+    // Console symbol of whatever stands on a cell, robots first, then the station and structures
+    fn entity_symbol(&self, robots: &[Robot], registry: &RoleRegistry, x: usize, y: usize, station_x: usize, station_y: usize) -> Option<String> {
+        let here: Vec<&Robot> = robots.iter().filter(|r| r.x == x && r.y == y).collect();
+        if let Some(robot) = here.iter().find(|r| !r.is_wreck() && !r.is_broken()) {
+            let letter = registry.get(robot.role).map_or('R', |definition| definition.symbol);
+            Some(format!("\x1b[31m {} \x1b[0m ", letter))
+        } else if here.iter().any(|r| r.is_broken()) {
+            Some("\x1b[35m B \x1b[0m ".to_string())
+        } else if !here.is_empty() {
+            Some("\x1b[90m X \x1b[0m ".to_string())
+        } else if x == station_x && y == station_y {
+            Some("\x1b[34m H \x1b[0m ".to_string())
+        } else {
+            self.structures.get(&(x, y)).map(|structure| structure.to_colored_symbol().to_string())
+        }
+    }
+
+    pub fn display_with_entities(&self, robots: &[Robot], station_x: usize, station_y: usize, registry: &RoleRegistry) {
         for y in 0..self.height {
             for x in 0..self.width {
-                let symbol = if robots.iter().any(|r| r.x == x && r.y == y) {
-                    "\x1b[31m R \x1b[0m "
-                } else if x == station_x && y == station_y {
-                    "\x1b[34m H \x1b[0m "
-                } else {
-                    self.grid[y][x].to_colored_symbol()
-                };
+                let symbol = self.entity_symbol(robots, registry, x, y, station_x, station_y)
+                    .unwrap_or_else(|| self.cell_symbol(x, y));
 
                 print!("{:<4}", symbol);
             }
@@ -104,27 +233,25 @@ impl Map {
         }
     }
 
-    pub fn display_with_fog(&self, robots: &[Robot], station_x: usize, station_y: usize, station: &Station) {
+    pub fn display_with_fog(&self, robots: &[Robot], station_x: usize, station_y: usize, station: &Station, registry: &RoleRegistry) {
         let mut visible_cells = HashSet::new();
 
         visible_cells.insert((station_x, station_y));
         for robot in robots {
             visible_cells.insert((robot.x, robot.y));
         }
-        for (&(x, y), _) in &station.discovered {
+        for &(x, y) in station.discovered.keys() {
             visible_cells.insert((x, y));
         }
 
         for y in 0..self.height {
             for x in 0..self.width {
-                let symbol = if robots.iter().any(|r| r.x == x && r.y == y) {
-                    "\x1b[31m R \x1b[0m "
-                } else if x == station_x && y == station_y {
-                    "\x1b[34m H \x1b[0m "
+                let symbol = if let Some(symbol) = self.entity_symbol(robots, registry, x, y, station_x, station_y) {
+                    symbol
                 } else if visible_cells.contains(&(x, y)) {
-                    self.grid[y][x].to_colored_symbol()
+                    self.cell_symbol(x, y)
                 } else {
-                    " ? "
+                    " ? ".to_string()
                 };
 
                 print!("{:<4}", symbol);
@@ -0,0 +1,113 @@
+use crate::map::Cell;
+use rand::{rngs::StdRng, Rng};
+
+// How the cells of one resource cluster spread out from their seed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClusterShape {
+    Vein,      // Winding line, like mineral seams
+    Field,     // Compact blob growing in every direction
+    Scattered, // Lone cells, the original uniform sprinkling
+}
+
+// Placement rules for one resource type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceLayout {
+    pub cell: Cell,
+    pub density: f32,        // Share of the map covered by this resource
+    pub cluster_size: usize, // Cells grown from each cluster seed
+    pub shape: ClusterShape,
+}
+
+const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+// Grows clusters from random empty cells until each resource reaches its density, or the map is full
+pub fn place_resources(grid: &mut [Vec<Cell>], layouts: &[ResourceLayout], rng: &mut StdRng) {
+    let area = grid.len() * grid.first().map_or(0, |row| row.len());
+    for layout in layouts {
+        let target = (area as f32 * layout.density) as usize;
+        let mut placed = 0;
+        while placed < target {
+            let empty = empty_cells(grid);
+            if empty.is_empty() {
+                break;
+            }
+            let seed = empty[rng.gen_range(0..empty.len())];
+            let size = match layout.shape {
+                ClusterShape::Scattered => 1,
+                _ => layout.cluster_size.clamp(1, target - placed),
+            };
+            placed += grow_cluster(grid, seed, size, layout, rng);
+        }
+    }
+}
+
+fn empty_cells(grid: &[Vec<Cell>]) -> Vec<(usize, usize)> {
+    let mut empty = Vec::new();
+    for (y, row) in grid.iter().enumerate() {
+        for (x, &cell) in row.iter().enumerate() {
+            if cell == Cell::Empty {
+                empty.push((x, y));
+            }
+        }
+    }
+    empty
+}
+
+fn neighbour(grid: &[Vec<Cell>], (x, y): (usize, usize), (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+    let (nx, ny) = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+    (ny < grid.len() && nx < grid[ny].len()).then_some((nx, ny))
+}
+
+// Returns the number of cells turned into the resource
+fn grow_cluster(grid: &mut [Vec<Cell>], seed: (usize, usize), size: usize, layout: &ResourceLayout, rng: &mut StdRng) -> usize {
+    grid[seed.1][seed.0] = layout.cell;
+    let mut placed = 1;
+
+    match layout.shape {
+        ClusterShape::Scattered => {}
+        ClusterShape::Field => {
+            let mut cluster = vec![seed];
+            while placed < size {
+                let frontier: Vec<(usize, usize)> = cluster.iter()
+                    .flat_map(|&position| DIRECTIONS.iter().map(move |&direction| (position, direction)))
+                    .filter_map(|(position, direction)| neighbour(grid, position, direction))
+                    .filter(|&(x, y)| grid[y][x] == Cell::Empty)
+                    .collect();
+                if frontier.is_empty() {
+                    break;
+                }
+                let (x, y) = frontier[rng.gen_range(0..frontier.len())];
+                grid[y][x] = layout.cell;
+                cluster.push((x, y));
+                placed += 1;
+            }
+        }
+        ClusterShape::Vein => {
+            // A walker that mostly keeps its heading, crossing its own cells but not obstacles
+            let mut position = seed;
+            let mut direction = DIRECTIONS[rng.gen_range(0..DIRECTIONS.len())];
+            for _ in 0..size * 4 {
+                if placed >= size {
+                    break;
+                }
+                if rng.gen_bool(0.3) {
+                    direction = DIRECTIONS[rng.gen_range(0..DIRECTIONS.len())];
+                }
+                let Some((x, y)) = neighbour(grid, position, direction) else {
+                    direction = DIRECTIONS[rng.gen_range(0..DIRECTIONS.len())];
+                    continue;
+                };
+                match grid[y][x] {
+                    Cell::Empty => {
+                        grid[y][x] = layout.cell;
+                        placed += 1;
+                        position = (x, y);
+                    }
+                    cell if cell == layout.cell => position = (x, y),
+                    _ => direction = DIRECTIONS[rng.gen_range(0..DIRECTIONS.len())],
+                }
+            }
+        }
+    }
+    placed
+}
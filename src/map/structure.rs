@@ -0,0 +1,24 @@
+use crate::config::{BEACON_VISION_RANGE, OUTPOST_VISION_RANGE};
+
+// Buildings raised by builder robots on empty cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Structure {
+    Beacon,  // Keeps watch over the area around it
+    Outpost, // Secondary station where robots dock, deliver and recharge
+}
+
+impl Structure {
+    pub fn vision_range(&self) -> usize {
+        match self {
+            Structure::Beacon => BEACON_VISION_RANGE,
+            Structure::Outpost => OUTPOST_VISION_RANGE,
+        }
+    }
+
+    pub fn to_colored_symbol(&self) -> &'static str {
+        match self {
+            Structure::Beacon => "\x1b[34m T \x1b[0m ",
+            Structure::Outpost => "\x1b[34m O \x1b[0m ",
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::config::{PLAIN_MOVE_COST, SAND_MOVE_COST, MUD_MOVE_COST, WATER_MOVE_COST, ROCK_MOVE_COST, TERRAIN_SCALE};
+use noise::{NoiseFn, Perlin};
+
+// Ground under a cell, separate from what stands on it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Terrain {
+    #[default]
+    Plain,
+    Sand,
+    Mud,
+    Water,
+    Rock,
+}
+
+impl Terrain {
+    pub const ALL: [Terrain; 5] = [Terrain::Plain, Terrain::Sand, Terrain::Mud, Terrain::Water, Terrain::Rock];
+
+    // Actions a robot spends to enter a cell of this terrain
+    pub fn move_cost(&self) -> u32 {
+        match self {
+            Terrain::Plain => PLAIN_MOVE_COST,
+            Terrain::Sand => SAND_MOVE_COST,
+            Terrain::Mud => MUD_MOVE_COST,
+            Terrain::Water => WATER_MOVE_COST,
+            Terrain::Rock => ROCK_MOVE_COST,
+        }
+    }
+
+    pub fn to_colored_symbol(&self) -> &'static str {
+        match self {
+            Terrain::Plain => " E ",
+            Terrain::Sand => "\x1b[93m . \x1b[0m ",
+            Terrain::Mud => "\x1b[33m , \x1b[0m ",
+            Terrain::Water => "\x1b[34m ~ \x1b[0m ",
+            Terrain::Rock => "\x1b[37m ^ \x1b[0m ",
+        }
+    }
+
+    pub fn color(&self) -> [f32; 3] {
+        match self {
+            Terrain::Plain => [0.8, 0.8, 0.8],  // Light gray
+            Terrain::Sand => [0.93, 0.85, 0.6], // Pale yellow
+            Terrain::Mud => [0.55, 0.45, 0.3],  // Brown
+            Terrain::Water => [0.4, 0.6, 0.95], // Blue
+            Terrain::Rock => [0.6, 0.6, 0.6],   // Gray
+        }
+    }
+}
+
+// Terrain laid out by its own noise field: water and mud in the lows, sand and rock on the highs
+pub fn generate_terrain(width: usize, height: usize, seed: u64) -> Vec<Vec<Terrain>> {
+    let perlin = Perlin::new(seed.wrapping_add(1) as u32);
+    (0..height)
+        .map(|y| (0..width)
+            .map(|x| match perlin.get([x as f64 * TERRAIN_SCALE, y as f64 * TERRAIN_SCALE, seed as f64]) {
+                value if value < -0.45 => Terrain::Water,
+                value if value < -0.3 => Terrain::Mud,
+                value if value > 0.5 => Terrain::Rock,
+                value if value > 0.35 => Terrain::Sand,
+                _ => Terrain::Plain,
+            })
+            .collect())
+        .collect()
+}
@@ -1,6 +1,6 @@
-use crate::map::{Map, Cell};
-use crate::robot::{Robot, RobotRole, Direction};
-use crate::station::Station;
+use crate::map::{Map, Cell, Structure, Terrain};
+use crate::robot::{Robot, RobotId, RobotRole, Direction, RoleRegistry, Upgrade};
+use crate::simulation::{Simulation, SimulationEvent};
 use bevy::prelude::*;
 use bevy::input::mouse::{MouseScrollUnit, MouseWheel, MouseMotion};
 use bevy::input::ButtonInput;
@@ -9,17 +9,13 @@ use std::collections::HashSet;
 use crate::config::FOG_OF_WAR;
 use std::collections::HashMap;
 
+// Bevy system signatures (queries, param sets) are verbose by nature
+#[allow(clippy::type_complexity)]
 pub mod gui {
     use super::*;
     
-    #[derive(Resource)]
-    pub struct SimulationData {
-        pub map: Map,
-        pub robots: Vec<Robot>,
-        pub station: Station,
-        pub station_x: usize,
-        pub station_y: usize,
-    }
+    #[derive(Resource, Deref, DerefMut)]
+    pub struct SimulationData(pub Simulation);
     
     #[derive(Resource)]
     pub struct SimulationTickTimer {
@@ -43,24 +39,48 @@ pub mod gui {
         pub value: f32,
     }
     
+    impl Default for TickSpeedMultiplier {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
     impl TickSpeedMultiplier {
         pub fn new() -> Self {
             Self { value: 1.0 }
         }
     }
     
+    impl Default for SimulationPaused {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
     impl SimulationPaused {
         pub fn new() -> Self {
             Self { paused: false }
         }
     }
     
+    impl Default for TickCounter {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
     impl TickCounter {
         pub fn new() -> Self {
             Self { count: 0 }
         }
     }
     
+    impl Default for SimulationTickTimer {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
     impl SimulationTickTimer {
         pub fn new() -> Self {
             Self {
@@ -76,13 +96,19 @@ pub mod gui {
     pub struct TickCounterSprite;
     
     #[derive(Component)]
-    pub struct RobotSprite(pub usize);
+    pub struct RobotSprite(pub RobotId);
     
     #[derive(Component)]
-    pub struct DirectionIndicator(pub usize);
+    pub struct DirectionIndicator(pub RobotId);
     
     #[derive(Component)]
     pub struct StationSprite;
+
+    #[derive(Component)]
+    pub struct StructureSprite(pub (usize, usize));
+
+    #[derive(Component)]
+    pub struct RobotTooltip;
     
     #[derive(Component)]
     struct ResourceLegend;
@@ -101,8 +127,31 @@ pub mod gui {
 
     #[derive(Component)]
     pub struct RobotCounter(pub RobotRole);
+
+    #[derive(Component)]
+    pub struct ResearchCounter;
+
+    #[derive(Component)]
+    pub struct AnalysisCounter;
+
+    #[derive(Component)]
+    pub struct UpgradeCounter(pub Upgrade);
+
+    #[derive(Component)]
+    pub struct ProductionQueueText;
     
     pub const TILE_SIZE: f32 = 32.0;
+
+    // Sprite colour of a role, grey for roles missing from the registry
+    pub fn role_color(registry: &RoleRegistry, role: RobotRole) -> Color {
+        match registry.get(role) {
+            Some(definition) => {
+                let [r, g, b] = definition.color;
+                Color::srgb(r, g, b)
+            }
+            None => Color::srgb(0.5, 0.5, 0.5),
+        }
+    }
     
     // Component for the tick counter display
     #[derive(Component)]
@@ -124,7 +173,7 @@ pub mod gui {
         
         if FOG_OF_WAR {
             // Add station's initial vision
-            visible_cells.insert((simulation.station_x, simulation.station_y));
+            visible_cells.insert((simulation.station.x, simulation.station.y));
 
             // Add robots' initial positions
             for robot in &simulation.robots {
@@ -132,7 +181,7 @@ pub mod gui {
             }
 
             // Add discovered cells from the station
-            for (&(x, y), _) in &simulation.station.discovered {
+            for &(x, y) in simulation.station.discovered.keys() {
                 visible_cells.insert((x, y));
             }
         } else {
@@ -284,6 +333,19 @@ pub mod gui {
             ));
         });
         
+        // Tooltip describing the robots under the cursor, hidden until hovered
+        commands.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                padding: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.1, 0.1, 0.1).with_alpha(0.85)),
+            Text::new(""),
+            Visibility::Hidden,
+            RobotTooltip,
+        ));
+
         // Spawn map tiles
         for y in 0..simulation.map.height {
             for x in 0..simulation.map.width {
@@ -299,7 +361,7 @@ pub mod gui {
                 // Only render with actual cell colors if the cell is visible
                 let color = if is_visible {
                     match simulation.map.grid[y][x] {
-                        Cell::Empty => Color::srgb(0.8, 0.8, 0.8),    // Light gray
+                        Cell::Empty => terrain_color(simulation.map.terrain_at(x, y)),
                         Cell::Obstacle => Color::srgb(0.3, 0.3, 0.3), // Dark gray
                         Cell::Energy => Color::srgb(1.0, 0.8, 0.0),   // Gold
                         Cell::Mineral => Color::srgb(0.6, 0.3, 0.8),  // Purple
@@ -505,46 +567,61 @@ pub mod gui {
                 ));
             });
 
-            // Robot - Explorer
-            parent.spawn((
-                Node {
-                    flex_direction: FlexDirection::Row,
-                    align_items: AlignItems::Center,
-                    margin: UiRect::bottom(Val::Px(5.0)),
-                    padding: UiRect::all(Val::Px(5.0)),
-                    ..default()
-                },
-            )).with_children(|parent| {
-                // Colored square
-                parent.spawn((
-                    Node {
-                        width: Val::Px(20.0),
-                        height: Val::Px(20.0),
-                        margin: UiRect::right(Val::Px(10.0)),
-                        ..default()
-                    },
-                    BackgroundColor(Color::srgb(0.0, 1.0, 0.0)), // Green
-                ));
-                // Text label and count
+            // One row per registered robot role
+            for (role, definition) in simulation.registry.roles() {
                 parent.spawn((
                     Node {
                         flex_direction: FlexDirection::Row,
                         align_items: AlignItems::Center,
+                        margin: UiRect::bottom(Val::Px(5.0)),
+                        padding: UiRect::all(Val::Px(5.0)),
                         ..default()
                     },
                 )).with_children(|parent| {
+                    // Colored square
                     parent.spawn((
-                        Text::new("Explorer Robot: "),
+                        Node {
+                            width: Val::Px(20.0),
+                            height: Val::Px(20.0),
+                            margin: UiRect::right(Val::Px(10.0)),
+                            ..default()
+                        },
+                        BackgroundColor(role_color(&simulation.registry, role)),
                     ));
-                    // Count component that will be updated
+                    // Text label and count
                     parent.spawn((
-                        Text::new("0"),
-                        RobotCounter(RobotRole::Explorer),
-                    ));
+                        Node {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                    )).with_children(|parent| {
+                        parent.spawn((
+                            Text::new(format!("{} Robot: ", definition.name)),
+                        ));
+                        // Count component that will be updated
+                        parent.spawn((
+                            Text::new("0"),
+                            RobotCounter(role),
+                        ));
+                    });
                 });
+            }
+
+            // Title: Research
+            parent.spawn((
+                Node {
+                    margin: UiRect::new(Val::Px(0.0), Val::Px(0.0), Val::Px(10.0), Val::Px(5.0)),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    ..default()
+                },
+            )).with_children(|parent| {
+                parent.spawn((
+                    Text::new("Research"),
+                ));
             });
 
-            // Robot - Collector
+            // Research points
             parent.spawn((
                 Node {
                     flex_direction: FlexDirection::Row,
@@ -554,36 +631,16 @@ pub mod gui {
                     ..default()
                 },
             )).with_children(|parent| {
-                // Colored square
                 parent.spawn((
-                    Node {
-                        width: Val::Px(20.0),
-                        height: Val::Px(20.0),
-                        margin: UiRect::right(Val::Px(10.0)),
-                        ..default()
-                    },
-                    BackgroundColor(Color::srgb(1.0, 0.5, 0.0)), // Orange
+                    Text::new("Points: "),
                 ));
-                // Text label and count
                 parent.spawn((
-                    Node {
-                        flex_direction: FlexDirection::Row,
-                        align_items: AlignItems::Center,
-                        ..default()
-                    },
-                )).with_children(|parent| {
-                    parent.spawn((
-                        Text::new("Collector Robot: "),
-                    ));
-                    // Count component that will be updated
-                    parent.spawn((
-                        Text::new("0"),
-                        RobotCounter(RobotRole::Collector),
-                    ));
-                });
+                    Text::new("0"),
+                    ResearchCounter,
+                ));
             });
 
-            // Robot - Scientist
+            // Science deposits analysed by the scientists
             parent.spawn((
                 Node {
                     flex_direction: FlexDirection::Row,
@@ -593,40 +650,69 @@ pub mod gui {
                     ..default()
                 },
             )).with_children(|parent| {
-                // Colored square
                 parent.spawn((
-                    Node {
-                        width: Val::Px(20.0),
-                        height: Val::Px(20.0),
-                        margin: UiRect::right(Val::Px(10.0)),
-                        ..default()
-                    },
-                    BackgroundColor(Color::srgb(0.8, 0.0, 0.8)), // Purple
+                    Text::new("Analyses: "),
                 ));
-                // Text label and count
+                parent.spawn((
+                    Text::new("0"),
+                    AnalysisCounter,
+                ));
+            });
+
+            // One row per upgrade, with its level and how many robots carry it
+            for upgrade in Upgrade::ALL {
                 parent.spawn((
                     Node {
                         flex_direction: FlexDirection::Row,
                         align_items: AlignItems::Center,
+                        margin: UiRect::bottom(Val::Px(5.0)),
+                        padding: UiRect::all(Val::Px(5.0)),
                         ..default()
                     },
                 )).with_children(|parent| {
                     parent.spawn((
-                        Text::new("Scientist Robot: "),
+                        Text::new(format!("{}: ", upgrade)),
                     ));
-                    // Count component that will be updated
                     parent.spawn((
                         Text::new("0"),
-                        RobotCounter(RobotRole::Scientist),
+                        UpgradeCounter(upgrade),
                     ));
                 });
+            }
+
+            // Title: Production
+            parent.spawn((
+                Node {
+                    margin: UiRect::new(Val::Px(0.0), Val::Px(0.0), Val::Px(10.0), Val::Px(5.0)),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    ..default()
+                },
+            )).with_children(|parent| {
+                parent.spawn((
+                    Text::new("Production"),
+                ));
+            });
+
+            // Orders waiting at the station, the first one being built
+            parent.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    margin: UiRect::bottom(Val::Px(5.0)),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    ..default()
+                },
+            )).with_children(|parent| {
+                parent.spawn((
+                    Text::new("Queue empty"),
+                    ProductionQueueText,
+                ));
             });
         });
         
         // Spawn station
         let station_pos = Vec3::new(
-            simulation.station_x as f32 * TILE_SIZE,
-            -(simulation.station_y as f32 * TILE_SIZE),
+            simulation.station.x as f32 * TILE_SIZE,
+            -(simulation.station.y as f32 * TILE_SIZE),
             1.0,
         );
         
@@ -645,7 +731,7 @@ pub mod gui {
         // Create a map to track how many robots are at each position
         let mut robot_position_count: HashMap<(usize, usize), usize> = HashMap::new();
         
-        for (i, robot) in simulation.robots.iter().enumerate() {
+        for robot in simulation.robots.iter() {
             // Count robots at each position
             let position_count = robot_position_count.entry((robot.x, robot.y)).or_insert(0);
             *position_count += 1;
@@ -653,53 +739,55 @@ pub mod gui {
             // Calculate offset based on how many robots are already at this position
             let offset_angle = (*position_count as f32 - 1.0) * std::f32::consts::PI / 4.0;
             let offset_distance = if *position_count > 1 { TILE_SIZE * 0.3 } else { 0.0 };
-            let offset_x = offset_distance * offset_angle.cos();
-            let offset_y = offset_distance * offset_angle.sin();
-            
-            let robot_pos = Vec3::new(
-                robot.x as f32 * TILE_SIZE + offset_x,
-                -(robot.y as f32 * TILE_SIZE) + offset_y,
-                2.0,
-            );
+            let offset = Vec2::new(offset_distance * offset_angle.cos(), offset_distance * offset_angle.sin());
             
-            // Spawn the robot sprite
-            let robot_entity = commands.spawn((
+            spawn_robot_sprite(&mut commands, &simulation.registry, robot, offset);
+        }
+    }
+
+    // Offset of the direction indicator relative to its robot sprite
+    fn direction_indicator_offset(direction: Direction) -> Vec3 {
+        match direction {
+            Direction::North => Vec3::new(0.0, TILE_SIZE * 0.3, 0.1),
+            Direction::South => Vec3::new(0.0, -TILE_SIZE * 0.3, 0.1),
+            Direction::East => Vec3::new(TILE_SIZE * 0.3, 0.0, 0.1),
+            Direction::West => Vec3::new(-TILE_SIZE * 0.3, 0.0, 0.1),
+        }
+    }
+
+    // Spawns the sprite of a robot and its direction indicator, both keyed by the robot id
+    fn spawn_robot_sprite(commands: &mut Commands, registry: &RoleRegistry, robot: &Robot, offset: Vec2) {
+        let robot_pos = Vec3::new(
+            robot.x as f32 * TILE_SIZE + offset.x,
+            -(robot.y as f32 * TILE_SIZE) + offset.y,
+            2.0,
+        );
+        
+        // Spawn the robot sprite
+        let robot_entity = commands.spawn((
+            Sprite {
+                color: role_color(registry, robot.role),
+                custom_size: Some(Vec2::splat(TILE_SIZE * 0.8)),
+                ..default()
+            },
+            Transform::from_translation(robot_pos),
+            Visibility::Visible,
+            RobotSprite(robot.id),
+        )).id();
+        
+        // Spawn direction indicator as a child of the robot
+        commands.entity(robot_entity).with_children(|parent| {
+            parent.spawn((
                 Sprite {
-                    color: match robot.role {
-                        RobotRole::Explorer => Color::srgb(0.0, 1.0, 0.0),   // Green
-                        RobotRole::Collector => Color::srgb(1.0, 0.5, 0.0),  // Orange
-                        RobotRole::Scientist => Color::srgb(0.8, 0.0, 0.8),  // Purple
-                    },
-                    custom_size: Some(Vec2::splat(TILE_SIZE * 0.8)),
+                    color: Color::srgb(1.0, 1.0, 1.0), // White
+                    custom_size: Some(Vec2::splat(TILE_SIZE * 0.2)),
                     ..default()
                 },
-                Transform::from_translation(robot_pos),
+                Transform::from_translation(direction_indicator_offset(robot.direction)),
                 Visibility::Visible,
-                RobotSprite(i),
-            )).id();
-            
-            // Calculate direction indicator position
-            let indicator_offset = match robot.direction {
-                Direction::North => Vec3::new(0.0, TILE_SIZE * 0.3, 0.1),
-                Direction::South => Vec3::new(0.0, -TILE_SIZE * 0.3, 0.1),
-                Direction::East => Vec3::new(TILE_SIZE * 0.3, 0.0, 0.1),
-                Direction::West => Vec3::new(-TILE_SIZE * 0.3, 0.0, 0.1),
-            };
-            
-            // Spawn direction indicator as a child of the robot
-            commands.entity(robot_entity).with_children(|parent| {
-                parent.spawn((
-                    Sprite {
-                        color: Color::srgb(1.0, 1.0, 1.0), // White
-                        custom_size: Some(Vec2::splat(TILE_SIZE * 0.2)),
-                        ..default()
-                    },
-                    Transform::from_translation(indicator_offset),
-                    Visibility::Visible,
-                    DirectionIndicator(i),
-                ));
-            });
-        }
+                DirectionIndicator(robot.id),
+            ));
+        });
     }
     
     pub fn tick_simulation(
@@ -710,12 +798,12 @@ pub mod gui {
         paused: Res<SimulationPaused>,
         mut commands: Commands,
         mut param_set: ParamSet<(
-            Query<(&mut Transform, &RobotSprite)>,
+            Query<(Entity, &mut Transform, &mut Sprite, &RobotSprite)>,
             Query<(&mut Transform, &DirectionIndicator)>
         )>,
     ) {
-        // Skip updating if simulation is paused
-        if paused.paused {
+        // Skip updating if simulation is paused or the mission is over
+        if paused.paused || sim.is_finished() {
             return;
         }
         
@@ -726,106 +814,51 @@ pub mod gui {
             // Show tick count in console
             println!("Tick: {}", tick_counter.count);
             
-            // Create local copies of the values we need
-            let station_x = sim.station_x;
-            let station_y = sim.station_y;
-            
-            // Clone map and station outside the loop to avoid borrowing issues
-            let mut map_clone = sim.map.clone();
-            let mut station_clone = sim.station.clone();
-            
-            // Update explorer positions in the station
-            {
-                // Collect references to all robots
-                let robot_refs: Vec<&Robot> = sim.robots.iter().collect();
-                station_clone.update_explorer_positions(&robot_refs);
-            }
-            
-            // Update robots one at a time
-            for robot in &mut sim.robots {
-                // Update the robot with the cloned data
-                robot.act(&mut map_clone, station_x, station_y, &mut station_clone);
-            }
+            let events = sim.step();
+            for event in &events {
+                println!("{}", event);
 
-            // Try to create a new robot if we have enough resources
-            if let Some(new_robot) = station_clone.maybe_create_robot() {
-                let robot_index = sim.robots.len();
-                let robot_role = new_robot.role;
-                let robot_direction = new_robot.direction;
-                let robot_x = new_robot.x;
-                let robot_y = new_robot.y;
-                sim.robots.push(new_robot);
-
-                // Spawn the new robot sprite
-                let robot_pos = Vec3::new(
-                    robot_x as f32 * TILE_SIZE,
-                    -(robot_y as f32 * TILE_SIZE),
-                    2.0,
-                );
-
-                // Spawn the robot sprite
-                let robot_entity = commands.spawn((
-                    Sprite {
-                        color: match robot_role {
-                            RobotRole::Explorer => Color::srgb(0.0, 1.0, 0.0),   // Green
-                            RobotRole::Collector => Color::srgb(1.0, 0.5, 0.0),  // Orange
-                            RobotRole::Scientist => Color::srgb(0.8, 0.0, 0.8),  // Purple
-                        },
-                        custom_size: Some(Vec2::splat(TILE_SIZE * 0.8)),
-                        ..default()
-                    },
-                    Transform::from_translation(robot_pos),
-                    Visibility::Visible,
-                    RobotSprite(robot_index),
-                )).id();
-
-                // Calculate direction indicator position
-                let indicator_offset = match robot_direction {
-                    Direction::North => Vec3::new(0.0, TILE_SIZE * 0.3, 0.1),
-                    Direction::South => Vec3::new(0.0, -TILE_SIZE * 0.3, 0.1),
-                    Direction::East => Vec3::new(TILE_SIZE * 0.3, 0.0, 0.1),
-                    Direction::West => Vec3::new(-TILE_SIZE * 0.3, 0.0, 0.1),
-                };
-
-                // Spawn direction indicator as a child of the robot
-                commands.entity(robot_entity).with_children(|parent| {
-                    parent.spawn((
-                        Sprite {
-                            color: Color::srgb(1.0, 1.0, 1.0), // White
-                            custom_size: Some(Vec2::splat(TILE_SIZE * 0.2)),
-                            ..default()
-                        },
-                        Transform::from_translation(indicator_offset),
-                        Visibility::Visible,
-                        DirectionIndicator(robot_index),
-                    ));
-                });
+                // Spawn sprites for robots built during this tick
+                if let SimulationEvent::RobotCreated { robot, .. } = event
+                    && let Some(new_robot) = sim.robot(*robot)
+                {
+                    spawn_robot_sprite(&mut commands, &sim.registry, new_robot, Vec2::ZERO);
+                }
             }
             
-            // Update the main simulation with changes
-            sim.map = map_clone;
-            sim.station = station_clone;
-            
             // Track how many robots are at each position
             let mut robot_position_count: HashMap<(usize, usize), usize> = HashMap::new();
-            let mut robot_position_index: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+            let mut robot_position_ids: HashMap<(usize, usize), Vec<RobotId>> = HashMap::new();
             
-            // First, count robots at each position and track their indices
-            for (i, robot) in sim.robots.iter().enumerate() {
+            // First, count robots at each position and track their ids
+            for robot in sim.robots.iter() {
                 *robot_position_count.entry((robot.x, robot.y)).or_insert(0) += 1;
-                robot_position_index.entry((robot.x, robot.y)).or_insert_with(Vec::new).push(i);
+                robot_position_ids.entry((robot.x, robot.y)).or_default().push(robot.id);
             }
             
             // Update robot positions in the UI
             {
                 let mut robot_query = param_set.p0();
-                for (mut transform, robot_sprite) in robot_query.iter_mut() {
-                    let robot = &sim.robots[robot_sprite.0];
+                for (entity, mut transform, mut sprite, robot_sprite) in robot_query.iter_mut() {
+                    // Sprites of robots removed from the simulation go away with them
+                    let Some(robot) = sim.robot(robot_sprite.0) else {
+                        commands.entity(entity).despawn_recursive();
+                        continue;
+                    };
                     let pos = (robot.x, robot.y);
+
+                    // Robots with a flat battery stay on the map as grey wrecks, broken ones turn dark red
+                    sprite.color = if robot.is_wreck() {
+                        Color::srgb(0.4, 0.4, 0.4)
+                    } else if robot.is_broken() {
+                        Color::srgb(0.5, 0.1, 0.1)
+                    } else {
+                        role_color(&sim.registry, robot.role)
+                    };
                     
-                    // Find this robot's index among robots at the same position
-                    let position_index = robot_position_index.get(&pos)
-                        .and_then(|indices| indices.iter().position(|&idx| idx == robot_sprite.0))
+                    // Find this robot's rank among robots at the same position
+                    let position_index = robot_position_ids.get(&pos)
+                        .and_then(|ids| ids.iter().position(|&id| id == robot.id))
                         .unwrap_or(0);
                     
                     // Calculate offset based on position and total robots at that position
@@ -848,17 +881,10 @@ pub mod gui {
             {
                 let mut indicator_query = param_set.p1();
                 for (mut transform, indicator) in indicator_query.iter_mut() {
-                    let robot = &sim.robots[indicator.0];
-                    
-                    // Update indicator position based on robot's current direction
-                    let indicator_offset = match robot.direction {
-                        Direction::North => Vec3::new(0.0, TILE_SIZE * 0.3, 0.1),
-                        Direction::South => Vec3::new(0.0, -TILE_SIZE * 0.3, 0.1),
-                        Direction::East => Vec3::new(TILE_SIZE * 0.3, 0.0, 0.1),
-                        Direction::West => Vec3::new(-TILE_SIZE * 0.3, 0.0, 0.1),
-                    };
-                    
-                    transform.translation = indicator_offset;
+                    if let Some(robot) = sim.robot(indicator.0) {
+                        // Update indicator position based on robot's current direction
+                        transform.translation = direction_indicator_offset(robot.direction);
+                    }
                 }
             }
         }
@@ -913,7 +939,11 @@ pub mod gui {
         simulation: Res<SimulationData>,
         mut param_set: ParamSet<(
             Query<(&mut Text, &ResourceCounter)>,
-            Query<(&mut Text, &RobotCounter)>
+            Query<(&mut Text, &RobotCounter)>,
+            Query<(&mut Text, &ResearchCounter)>,
+            Query<(&mut Text, &UpgradeCounter)>,
+            Query<(&mut Text, &AnalysisCounter)>,
+            Query<(&mut Text, &ProductionQueueText)>
         )>,
     ) {
         // Count resources in the station
@@ -932,16 +962,9 @@ pub mod gui {
         }
 
         // Count robots by role
-        let mut explorer_count = 0;
-        let mut collector_count = 0;
-        let mut scientist_count = 0;
-
+        let mut role_counts: HashMap<RobotRole, usize> = HashMap::new();
         for robot in &simulation.robots {
-            match robot.role {
-                RobotRole::Explorer => explorer_count += 1,
-                RobotRole::Collector => collector_count += 1,
-                RobotRole::Scientist => scientist_count += 1,
-            }
+            *role_counts.entry(robot.role).or_insert(0) += 1;
         }
 
         // Update resource count text
@@ -953,19 +976,45 @@ pub mod gui {
                 Cell::Science => science_count.to_string(),
                 _ => "0".to_string(),
             };
+            let new_text = match simulation.station.storage_capacity.get(&counter.0) {
+                Some(capacity) => format!("{}/{}", new_text, capacity),
+                None => new_text,
+            };
             *text = Text::new(new_text);
         }
 
         // Update robot count text
         let mut robot_counters = param_set.p1();
         for (mut text, counter) in robot_counters.iter_mut() {
-            let new_text = match counter.0 {
-                RobotRole::Explorer => explorer_count.to_string(),
-                RobotRole::Collector => collector_count.to_string(),
-                RobotRole::Scientist => scientist_count.to_string(),
-            };
+            let new_text = role_counts.get(&counter.0).copied().unwrap_or(0).to_string();
             *text = Text::new(new_text);
         }
+
+        // Update research points and upgrade levels
+        for (mut text, _) in param_set.p2().iter_mut() {
+            *text = Text::new(simulation.station.research_points.to_string());
+        }
+        for (mut text, _) in param_set.p4().iter_mut() {
+            *text = Text::new(simulation.station.research_results.len().to_string());
+        }
+        for (mut text, counter) in param_set.p3().iter_mut() {
+            let level = simulation.station.technology.level(counter.0);
+            let fitted = simulation.robots.iter()
+                .filter(|robot| level > 0 && robot.upgrades.level(counter.0) >= level)
+                .count();
+            *text = Text::new(format!("level {} ({}/{} robots)", level, fitted, simulation.robots.len()));
+        }
+
+        // Update the production queue
+        let queue = &simulation.station.production_queue;
+        let queue_text = if queue.is_empty() {
+            "Queue empty".to_string()
+        } else {
+            queue.iter().map(|order| order.to_string()).collect::<Vec<_>>().join("\n")
+        };
+        for (mut text, _) in param_set.p5().iter_mut() {
+            *text = Text::new(queue_text.clone());
+        }
     }
 
     // Component to store tile position for fog of war updates
@@ -976,6 +1025,25 @@ pub mod gui {
         pub discovered: bool,
     }
 
+    fn terrain_color(terrain: Terrain) -> Color {
+        let [r, g, b] = terrain.color();
+        Color::srgb(r, g, b)
+    }
+
+    // Colour of a discovered tile, resources fading towards empty ground as their deposit is mined
+    fn tile_color(map: &Map, x: usize, y: usize) -> Color {
+        let empty = terrain_color(map.terrain_at(x, y));
+        let full = match map.grid[y][x] {
+            Cell::Empty => return empty,
+            Cell::Obstacle => return Color::srgb(0.3, 0.3, 0.3), // Dark gray
+            Cell::Energy => Color::srgb(1.0, 0.8, 0.0),   // Gold
+            Cell::Mineral => Color::srgb(0.6, 0.3, 0.8),  // Purple
+            Cell::Science => Color::srgb(0.0, 0.8, 1.0),  // Cyan
+        };
+        let depletion = map.deposits.get(&(x, y)).map_or(0.0, |deposit| deposit.depletion());
+        full.mix(&empty, depletion * 0.7)
+    }
+
     // System to update tile visibility based on fog of war
     pub fn update_fog_of_war(
         simulation: Res<SimulationData>,
@@ -990,13 +1058,7 @@ pub mod gui {
                 if pos.discovered {
                     // Determine the actual current state of the cell
                     // It might have changed if resources were collected
-                    sprite.color = match simulation.map.grid[pos.y][pos.x] {
-                        Cell::Empty => Color::srgb(0.8, 0.8, 0.8),    // Light gray
-                        Cell::Obstacle => Color::srgb(0.3, 0.3, 0.3), // Dark gray
-                        Cell::Energy => Color::srgb(1.0, 0.8, 0.0),   // Gold
-                        Cell::Mineral => Color::srgb(0.6, 0.3, 0.8),  // Purple
-                        Cell::Science => Color::srgb(0.0, 0.8, 1.0),  // Cyan
-                    };
+                    sprite.color = tile_color(&simulation.map, pos.x, pos.y);
                 }
             }
             return;
@@ -1006,7 +1068,7 @@ pub mod gui {
         let mut visible_cells = HashSet::new();
         
         // Add station's initial vision
-        visible_cells.insert((simulation.station_x, simulation.station_y));
+        visible_cells.insert((simulation.station.x, simulation.station.y));
 
         // Add robots' initial positions
         for robot in &simulation.robots {
@@ -1014,7 +1076,7 @@ pub mod gui {
         }
 
         // Add discovered cells from the station
-        for (&(x, y), _) in &simulation.station.discovered {
+        for &(x, y) in simulation.station.discovered.keys() {
             visible_cells.insert((x, y));
         }
         
@@ -1031,13 +1093,7 @@ pub mod gui {
                 
                 // Always update the color to reflect current state
                 // This ensures resources disappear when collected
-                sprite.color = match simulation.map.grid[pos.y][pos.x] {
-                    Cell::Empty => Color::srgb(0.8, 0.8, 0.8),    // Light gray
-                    Cell::Obstacle => Color::srgb(0.3, 0.3, 0.3), // Dark gray
-                    Cell::Energy => Color::srgb(1.0, 0.8, 0.0),   // Gold
-                    Cell::Mineral => Color::srgb(0.6, 0.3, 0.8),  // Purple
-                    Cell::Science => Color::srgb(0.0, 0.8, 1.0),  // Cyan
-                };
+                sprite.color = tile_color(&simulation.map, pos.x, pos.y);
             } else if !pos.discovered {
                 // Tile has not been discovered yet, keep it black
                 sprite.color = Color::srgb(0.0, 0.0, 0.0); // Black
@@ -1045,13 +1101,91 @@ pub mod gui {
         }
     }
 
-    // System to update window title with tick count
+    // System showing the state of the robots under the mouse cursor
+    pub fn update_robot_tooltip(
+        simulation: Res<SimulationData>,
+        windows: Query<&Window>,
+        cameras: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+        mut tooltip_query: Query<(&mut Node, &mut Text, &mut Visibility), With<RobotTooltip>>,
+    ) {
+        let Ok((mut node, mut text, mut visibility)) = tooltip_query.get_single_mut() else {
+            return;
+        };
+        *visibility = Visibility::Hidden;
+
+        let Ok(window) = windows.get_single() else { return };
+        let Ok((camera, camera_transform)) = cameras.get_single() else { return };
+        let Some(cursor) = window.cursor_position() else { return };
+        let Ok(world) = camera.viewport_to_world_2d(camera_transform, cursor) else { return };
+
+        // Tiles are centred on multiples of TILE_SIZE, with y growing downwards on the map
+        let tile_x = (world.x / TILE_SIZE).round();
+        let tile_y = (-world.y / TILE_SIZE).round();
+        if tile_x < 0.0 || tile_y < 0.0 {
+            return;
+        }
+        let (tile_x, tile_y) = (tile_x as usize, tile_y as usize);
+
+        let lines: Vec<String> = simulation.robots.iter()
+            .filter(|robot| robot.x == tile_x && robot.y == tile_y)
+            .map(|robot| {
+                let mut line = format!("{}: {}\n  why: {}\n  battery: {}/{}\n  upgrades: {}", robot.label(), robot.state, robot.state_reason,
+                    robot.battery, robot.battery_capacity(), robot.upgrades);
+                if let Some(progress) = robot.action {
+                    line.push_str(&format!("\n  busy: {} ({} ticks left)", progress.action, progress.remaining));
+                }
+                line
+            })
+            .collect();
+        if lines.is_empty() {
+            return;
+        }
+
+        *text = Text::new(lines.join("\n"));
+        node.left = Val::Px(cursor.x + 16.0);
+        node.top = Val::Px(cursor.y + 16.0);
+        *visibility = Visibility::Visible;
+    }
+
+    // System spawning a sprite for every structure raised since the last frame
+    pub fn update_structure_sprites(
+        mut commands: Commands,
+        simulation: Res<SimulationData>,
+        sprites: Query<&StructureSprite>,
+    ) {
+        let drawn: HashSet<(usize, usize)> = sprites.iter().map(|sprite| sprite.0).collect();
+        for (&(x, y), structure) in simulation.map.structures.iter() {
+            if drawn.contains(&(x, y)) {
+                continue;
+            }
+            let (color, size) = match structure {
+                Structure::Beacon => (Color::srgb(0.0, 0.2, 0.6), 0.5),  // Dark blue
+                Structure::Outpost => (Color::srgb(0.6, 0.0, 0.0), 0.9), // Dark red, like a smaller station
+            };
+            commands.spawn((
+                Sprite {
+                    color,
+                    custom_size: Some(Vec2::splat(TILE_SIZE * size)),
+                    ..default()
+                },
+                Transform::from_translation(Vec3::new(x as f32 * TILE_SIZE, -(y as f32 * TILE_SIZE), 1.5)),
+                Visibility::Visible,
+                StructureSprite((x, y)),
+            ));
+        }
+    }
+
+    // System to update window title with tick count and the mission outcome
     pub fn update_window_title(
         tick_counter: Res<TickCounter>,
+        simulation: Res<SimulationData>,
         mut windows: Query<&mut Window>,
     ) {
         if let Ok(mut window) = windows.get_single_mut() {
-            window.title = format!("Projet Essaim 🌍 - Tick: {}", tick_counter.count);
+            window.title = match &simulation.result {
+                Some(result) => format!("Projet Essaim 🌍 - Mission {}", result),
+                None => format!("Projet Essaim 🌍 - Tick: {}", tick_counter.count),
+            };
         }
     }
 
@@ -1119,12 +1253,11 @@ pub mod gui {
         query: Query<&Children, With<SpeedIndicator>>,
         mut text_query: Query<&mut Text>,
     ) {
-        if let Ok(children) = query.get_single() {
-            if let Some(&first_child) = children.first() {
-                if let Ok(mut text) = text_query.get_mut(first_child) {
-                    *text = Text::new(format!("Speed: {:.1}x", speed_multiplier.value));
-                }
-            }
+        if let Ok(children) = query.get_single()
+            && let Some(&first_child) = children.first()
+            && let Ok(mut text) = text_query.get_mut(first_child)
+        {
+            *text = Text::new(format!("Speed: {:.1}x", speed_multiplier.value));
         }
     }
     
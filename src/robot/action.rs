@@ -0,0 +1,40 @@
+use crate::config::{PICKUP_DURATION, DEPOSIT_DURATION, ANALYSIS_DURATION, BUILD_DURATION};
+use std::fmt;
+
+// Work that keeps a robot busy for several ticks, see `Simulation::step`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Pickup,
+    Deposit,
+    Analysis,
+    Build,
+}
+
+impl Action {
+    // Ticks spent on the action, including the one it starts on
+    pub fn duration(&self) -> u32 {
+        match self {
+            Action::Pickup => PICKUP_DURATION,
+            Action::Deposit => DEPOSIT_DURATION,
+            Action::Analysis => ANALYSIS_DURATION,
+            Action::Build => BUILD_DURATION,
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Pickup => write!(f, "Pickup"),
+            Action::Deposit => write!(f, "Deposit"),
+            Action::Analysis => write!(f, "Analysis"),
+            Action::Build => write!(f, "Build"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActionInProgress {
+    pub action: Action,
+    pub remaining: u32, // Ticks left before the robot can act again
+}
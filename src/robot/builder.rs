@@ -0,0 +1,65 @@
+use crate::map::{Map, Cell, Structure};
+use crate::station::Station;
+use crate::robot::{Robot, RobotBehavior, RoleDefinition, RobotState, Action};
+use crate::config::{BEACON_RECIPE, BEACON_SPACING};
+
+pub struct BuilderBehavior;
+
+impl RobotBehavior for BuilderBehavior {
+    fn act(&self, robot: &mut Robot, _role: &RoleDefinition, map: &mut Map, station_x: usize, station_y: usize, station: &mut Station) {
+        robot.act_as_builder(map, station_x, station_y, station);
+    }
+}
+
+impl Robot {
+    // Fetches the beacon materials at the station and raises beacons on empty cells away from the station
+    pub fn act_as_builder(&mut self, map: &mut Map, station_x: usize, station_y: usize, station: &mut Station) {
+        let missing: Vec<(Cell, usize)> = BEACON_RECIPE.iter()
+            .map(|&(cell, amount)| (cell, amount.saturating_sub(self.inventory.count(cell))))
+            .filter(|&(_, amount)| amount > 0)
+            .collect();
+        if !missing.is_empty() {
+            if self.x != station_x || self.y != station_y {
+                self.set_state(RobotState::ReturningToStation, "needs building materials");
+                self.move_dijkstra_to(map, station_x, station_y);
+                return;
+            }
+            if !station.spend(&missing) {
+                self.set_state(RobotState::Idle, "waiting for building materials");
+                return;
+            }
+            for (cell, amount) in missing {
+                for _ in 0..amount {
+                    self.inventory.add(cell);
+                }
+            }
+        }
+
+        match self.find_build_site(map, station_x, station_y) {
+            Some(site) if site == (self.x, self.y) => {
+                map.build(site.0, site.1, Structure::Beacon);
+                self.inventory.drain();
+                self.set_state(RobotState::Building { x: site.0, y: site.1 }, "beacon raised on a free build site");
+                self.begin_action(Action::Build);
+            }
+            Some((site_x, site_y)) => {
+                self.set_state(RobotState::TravellingToSite { x: site_x, y: site_y }, "nearest free build site");
+                self.move_dijkstra_to(map, site_x, site_y);
+            }
+            None => {
+                self.set_state(RobotState::Searching, "no build site known");
+                self.move_smart_towards_unknown(map);
+            }
+        }
+    }
+
+    // Nearest empty cell far enough from the station and from every other structure
+    pub fn find_build_site(&self, map: &Map, station_x: usize, station_y: usize) -> Option<(usize, usize)> {
+        let far_from = |(x, y): (usize, usize), (ox, oy): (usize, usize)| x.abs_diff(ox) + y.abs_diff(oy) >= BEACON_SPACING;
+        self.find_nearest_position(map, |position, cell| {
+            cell == Cell::Empty
+                && far_from(position, (station_x, station_y))
+                && map.structures.keys().all(|&other| far_from(position, other))
+        })
+    }
+}
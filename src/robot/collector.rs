@@ -1,31 +1,83 @@
 use crate::map::{Map, Cell};
 use crate::station::Station;
-use crate::robot::Robot;
+use crate::station::tasks::Allocation;
+use crate::robot::{Robot, RobotBehavior, RoleDefinition, RobotState, Action};
+
+pub struct CollectorBehavior;
+
+impl RobotBehavior for CollectorBehavior {
+    fn act(&self, robot: &mut Robot, _role: &RoleDefinition, map: &mut Map, station_x: usize, station_y: usize, station: &mut Station) {
+        robot.act_as_collector(map, station_x, station_y, station);
+    }
+}
 
 impl Robot {
-    pub fn act_as_collector(&mut self, map: &mut Map, station_x: usize, station_y: usize, station: &mut Station) {
-        self.vision(map, 2, station);
+    // Restricts this collector to a single resource type, or lifts the restriction with `None`
+    pub fn set_target_resource(&mut self, target: Option<Cell>) {
+        self.target_resource = target;
+        self.current_path.clear();
+    }
+
+    // Nearest resource posted on the task board that this collector wants and no other robot has claimed
+    pub fn find_nearest_unclaimed_resource(&self, map: &Map, station: &Station) -> Option<(usize, usize)> {
+        self.find_nearest_position(map, |position, cell| {
+            station.tasks.tasks.contains_key(&position)
+                && (cell == Cell::Mineral || cell == Cell::Energy)
+                && self.target_resource.is_none_or(|target| target == cell)
+                && self.inventory.can_carry(cell)
+                && station.tasks.is_available(position, self.id)
+        })
+    }
 
+    pub fn act_as_collector(&mut self, map: &mut Map, station_x: usize, station_y: usize, station: &mut Station) {
         let current_cell = map.grid[self.y][self.x];
-        if (current_cell == Cell::Mineral || current_cell == Cell::Energy) && self.collected.len() < 2 {
-            self.collected.push(current_cell);
-            map.grid[self.y][self.x] = Cell::Empty;
-            println!("Collector collected a resource! Total collected: {}", self.collected.len());
+        let wanted = (current_cell == Cell::Mineral || current_cell == Cell::Energy)
+            && self.target_resource.is_none_or(|target| target == current_cell);
+        if wanted {
+            station.tasks.claim((self.x, self.y), self.id);
         }
+        if wanted && self.mine(map) > 0 {
+            self.set_state(RobotState::Collecting, "mining a deposit");
+            self.begin_action(Action::Pickup);
+            println!("{} collected a resource! Load: {}/{}, {} left in deposit",
+                self.label(), self.inventory.load(), self.inventory.capacity(), map.quantity(self.x, self.y));
 
-        if self.collected.len() >= 2 {
+            // Keep mining while the deposit lasts and there is room left
+            if map.grid[self.y][self.x] == current_cell && self.inventory.can_carry(current_cell) {
+                return;
+            }
+        }
+
+        let target = if self.inventory.is_full() {
+            None
+        } else if station.tasks.allocation == Allocation::Auction {
+            station.tasks.claimed_by(self.id).map(|position| (position, "task won at auction"))
+        } else {
+            self.find_nearest_unclaimed_resource(map, station).map(|position| (position, "nearest unclaimed resource"))
+        };
+
+        if let Some(((target_x, target_y), reason)) = target {
+            station.tasks.claim((target_x, target_y), self.id);
+            self.set_state(RobotState::TravellingToResource { x: target_x, y: target_y }, reason);
+            self.move_dijkstra_to(map, target_x, target_y);
+            return;
+        }
+
+        station.tasks.release(self.id);
+        if !self.inventory.is_empty() {
             if self.x == station_x && self.y == station_y {
-                println!("Collector depositing {} resources at station", self.collected.len());
-                station.receive_resources(self.collected.drain(..).collect());
+                self.set_state(RobotState::Depositing, "cargo unloaded at the station");
+                println!("{} depositing {} resources at station", self.label(), self.inventory.len());
+                station.receive_resources(self.inventory.drain());
+                self.begin_action(Action::Deposit);
             } else {
+                let reason = if self.inventory.is_full() { "cargo full" } else { "no known resource fits" };
+                self.set_state(RobotState::ReturningToStation, reason);
                 self.move_dijkstra_to(map, station_x, station_y);
             }
         } else {
-            if let Some((target_x, target_y)) = self.find_nearest_resource_position(map) {
-                self.move_dijkstra_to(map, target_x, target_y);
-            } else {
-                self.move_smart_towards_unknown(map);
-            }
+            self.set_state(RobotState::Searching, "no resource known");
+            self.move_smart_towards_unknown(map);
         }
     }
 }
@@ -1,14 +1,18 @@
 use crate::map::Map;
 use crate::station::Station;
-use crate::robot::Robot;
+use crate::robot::{Robot, RobotBehavior, RoleDefinition, RobotState};
+
+pub struct ExplorerBehavior;
+
+impl RobotBehavior for ExplorerBehavior {
+    fn act(&self, robot: &mut Robot, _role: &RoleDefinition, map: &mut Map, station_x: usize, station_y: usize, station: &mut Station) {
+        robot.act_as_explorer(map, station_x, station_y, station);
+    }
+}
 
 impl Robot {
-    pub fn act_as_explorer(&mut self, map: &mut Map, station_x: usize, station_y: usize, station: &mut Station) {
-        self.vision(map, 2, station);
-        if self.x == station_x && self.y == station_y {
-            self.move_smart_towards_unknown_with_others(map, station.get_explorer_positions());
-        } else {
-            self.move_smart_towards_unknown_with_others(map, station.get_explorer_positions());
-        }
+    pub fn act_as_explorer(&mut self, map: &mut Map, _station_x: usize, _station_y: usize, station: &mut Station) {
+        self.set_state(RobotState::Exploring, "mapping unknown cells");
+        self.move_smart_towards_unknown_with_others(map, station.get_explorer_positions());
     }
 }
@@ -0,0 +1,55 @@
+use crate::map::Map;
+use crate::station::Station;
+use crate::robot::{Robot, RobotBehavior, RoleDefinition, RobotRole, RobotState, Action};
+
+pub struct HaulerBehavior;
+
+impl RobotBehavior for HaulerBehavior {
+    fn act(&self, robot: &mut Robot, _role: &RoleDefinition, map: &mut Map, station_x: usize, station_y: usize, station: &mut Station) {
+        robot.act_as_hauler(map, station_x, station_y, station);
+    }
+}
+
+impl Robot {
+    // Meets loaded collectors in the field so they can keep mining; the cargo itself is handed over by the simulation
+    pub fn act_as_hauler(&mut self, map: &mut Map, station_x: usize, station_y: usize, station: &mut Station) {
+        let at_station = self.x == station_x && self.y == station_y;
+        if at_station && !self.inventory.is_empty() {
+            self.set_state(RobotState::Depositing, "cargo unloaded at the station");
+            println!("{} depositing {} resources at station", self.label(), self.inventory.len());
+            station.receive_resources(self.inventory.drain());
+            self.begin_action(Action::Deposit);
+            return;
+        }
+
+        let pickup = if self.inventory.is_full() { None } else { self.find_loaded_collector(station) };
+        if let Some((target_x, target_y)) = pickup {
+            // Keep heading to where the collector was when the route was planned, then look again
+            let meeting = matches!(self.state, RobotState::MeetingCollector { .. });
+            let (target_x, target_y) = match self.path_target {
+                Some(target) if meeting && !self.current_path.is_empty() => target,
+                _ => {
+                    self.current_path.clear();
+                    self.path_target = None;
+                    (target_x, target_y)
+                }
+            };
+            self.set_state(RobotState::MeetingCollector { x: target_x, y: target_y }, "loaded collector");
+            self.move_dijkstra_to(map, target_x, target_y);
+        } else if !self.inventory.is_empty() || !at_station {
+            let reason = if self.inventory.is_full() { "cargo full" } else { "no collector to meet" };
+            self.set_state(RobotState::ReturningToStation, reason);
+            self.move_dijkstra_to(map, station_x, station_y);
+        } else {
+            self.set_state(RobotState::Idle, "waiting for loaded collectors");
+        }
+    }
+
+    fn find_loaded_collector(&self, station: &Station) -> Option<(usize, usize)> {
+        station.fleet.iter()
+            .filter(|entry| entry.role == RobotRole::Collector && entry.load > 0 && entry.position != (station.x, station.y))
+            .filter(|entry| !self.unreachable.contains(&entry.position))
+            .map(|entry| entry.position)
+            .min_by_key(|&(x, y)| self.x.abs_diff(x) + self.y.abs_diff(y))
+    }
+}
@@ -0,0 +1,78 @@
+use crate::map::Cell;
+use crate::config::{ENERGY_WEIGHT, MINERAL_WEIGHT, SCIENCE_WEIGHT};
+
+// Cargo space used by one unit of a resource
+pub fn resource_weight(cell: Cell) -> usize {
+    match cell {
+        Cell::Energy => ENERGY_WEIGHT,
+        Cell::Mineral => MINERAL_WEIGHT,
+        Cell::Science => SCIENCE_WEIGHT,
+        Cell::Empty | Cell::Obstacle => 0,
+    }
+}
+
+// Cargo hold of a robot, bounded by total weight rather than item count
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inventory {
+    items: Vec<Cell>,
+    capacity: usize,
+}
+
+impl Inventory {
+    pub fn new(capacity: usize) -> Self {
+        Self { items: Vec::new(), capacity }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+    }
+
+    pub fn load(&self) -> usize {
+        self.items.iter().map(|cell| resource_weight(*cell)).sum()
+    }
+
+    pub fn free_capacity(&self) -> usize {
+        self.capacity.saturating_sub(self.load())
+    }
+
+    pub fn can_carry(&self, cell: Cell) -> bool {
+        resource_weight(cell) > 0 && resource_weight(cell) <= self.free_capacity()
+    }
+
+    // Returns false, leaving the inventory untouched, when the resource does not fit
+    pub fn add(&mut self, cell: Cell) -> bool {
+        if !self.can_carry(cell) {
+            return false;
+        }
+        self.items.push(cell);
+        true
+    }
+
+    pub fn count(&self, cell: Cell) -> usize {
+        self.items.iter().filter(|&&item| item == cell).count()
+    }
+
+    pub fn items(&self) -> &[Cell] {
+        &self.items
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.free_capacity() == 0
+    }
+
+    pub fn drain(&mut self) -> Vec<Cell> {
+        std::mem::take(&mut self.items)
+    }
+}
@@ -1,14 +1,33 @@
-use crate::map::{Map, Cell};
+use crate::map::{Map, Cell, Terrain, extraction_rate};
+use crate::config::{BATTERY_CAPACITY, MOVE_ENERGY_COST, SENSE_ENERGY_COST, BATTERY_SAFETY_MARGIN, LOAD_SPEED_PENALTY, ACTION_POINTS};
 use rand::Rng;
-use std::collections::{HashSet, VecDeque};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
 
 mod explorer;
 mod collector;
 mod scientist;
-
-pub use explorer::*;
-pub use collector::*;
-pub use scientist::*;
+mod hauler;
+mod builder;
+mod relay;
+mod role;
+mod state;
+mod inventory;
+mod upgrade;
+mod action;
+
+pub use explorer::ExplorerBehavior;
+pub use collector::CollectorBehavior;
+pub use scientist::{ScientistBehavior, ResearchResult};
+pub use hauler::HaulerBehavior;
+pub use builder::BuilderBehavior;
+pub use relay::RelayBehavior;
+pub use role::{RobotBehavior, RoleDefinition, RoleRegistry};
+pub use state::{RobotState, StateTransition};
+pub use inventory::{Inventory, resource_weight};
+pub use upgrade::{Upgrade, Upgrades};
+pub use action::{Action, ActionInProgress};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Direction {
@@ -18,41 +37,212 @@ pub enum Direction {
     West,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RobotRole {
     Explorer,
     Collector,
     Scientist,
+    Hauler,    // Shuttles cargo from collectors to the station
+    Builder,   // Raises structures on empty cells
+    Relay,     // Parks far out to extend vision coverage
+    Custom(&'static str), // Role defined outside this crate, see `RoleRegistry`
+}
+
+impl fmt::Display for RobotRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RobotRole::Custom(name) => write!(f, "{}", name),
+            role => write!(f, "{:?}", role),
+        }
+    }
 }
 
+// Identifier handed out by the simulation, stable for the whole life of the robot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct RobotId(pub u32);
+
+impl fmt::Display for RobotId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+// Movement cost to each reachable position
+pub type PathCosts = HashMap<(usize, usize), usize>;
+// Previous step on the cheapest route to each position
+type Trail = HashMap<(usize, usize), (usize, usize)>;
+
 #[derive(Debug, Clone)]
 pub struct Robot {
+    pub id: RobotId,
+    pub name: Option<String>,
     pub x: usize,
     pub y: usize,
     pub direction: Direction,
     pub role: RobotRole,
     pub discovered: Vec<((usize, usize), Cell)>,
-    pub collected: Vec<Cell>,
-    pub target_resource: Option<Cell>,
+    pub inventory: Inventory,
+    pub target_resource: Option<Cell>,   // Only this resource type is collected when set
     pub current_path: Vec<(usize, usize)>,
     pub preferred_direction: Option<(isize, isize)>,
+    pub path_target: Option<(usize, usize)>,
+    pub battery: u32,
+    pub state: RobotState,
+    pub state_reason: &'static str,
+    pub transitions: Vec<StateTransition>, // Pending events, see `take_transitions`
+    pub towing: Option<RobotId>,           // Broken robot being brought home by this one
+    pub towed_by: Option<RobotId>,
+    pub upgrades: Upgrades,                // Refitted to the station technology on every visit
+    pub action: Option<ActionInProgress>,
+    pub speed_points: u32,                 // Unspent speed carried over to the next tick
+    pub move_debt: u32,                    // Extra points owed for entering slow terrain
+    pub impassable: Vec<Terrain>,          // Terrain of the role this robot cannot enter
+    pub unreachable: HashSet<(usize, usize)>, // Targets this robot found no path to
+    pub research_results: Vec<ResearchResult>, // Analyses not yet reported to the station
 }
 
 impl Robot {
     pub fn new(x: usize, y: usize, direction: Direction, role: RobotRole) -> Self {
         Self {
+            id: RobotId::default(),
+            name: None,
             x,
             y,
             direction,
             role,
             discovered: Vec::new(),
-            collected: Vec::new(),
+            inventory: Inventory::new(0),
             target_resource: None,
             current_path: Vec::new(),
             preferred_direction: None,
+            path_target: None,
+            battery: BATTERY_CAPACITY,
+            state: RobotState::Idle,
+            state_reason: "just built",
+            transitions: Vec::new(),
+            towing: None,
+            towed_by: None,
+            upgrades: Upgrades::default(),
+            action: None,
+            speed_points: 0,
+            move_debt: 0,
+            impassable: Vec::new(),
+            unreachable: HashSet::new(),
+            research_results: Vec::new(),
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    // Human-readable name used in logs, e.g. "Scout #3" or "Collector #4"
+    pub fn label(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{} {}", name, self.id),
+            None => format!("{} {}", self.role, self.id),
+        }
+    }
+
+    // Switches state, recording a transition event when the state actually changes
+    pub fn set_state(&mut self, state: RobotState, reason: &'static str) {
+        if self.state == state {
+            return;
+        }
+        self.transitions.push(StateTransition { from: self.state, to: state, reason });
+        self.state = state;
+        self.state_reason = reason;
+    }
+
+    // Hands over the transitions recorded since the last call
+    pub fn take_transitions(&mut self) -> Vec<StateTransition> {
+        std::mem::take(&mut self.transitions)
+    }
+
+    // A robot with an empty battery stays on the map as a wreck
+    pub fn is_wreck(&self) -> bool {
+        self.battery == 0
+    }
+
+    pub fn battery_capacity(&self) -> u32 {
+        BATTERY_CAPACITY + self.upgrades.battery_bonus()
+    }
+
+    // Speed points gained per tick, slowed down by the weight of the cargo
+    pub fn speed(&self, definition: &RoleDefinition) -> u32 {
+        let penalty = self.inventory.load() as u32 * LOAD_SPEED_PENALTY;
+        (definition.speed + self.upgrades.speed_bonus()).saturating_sub(penalty).max(1)
+    }
+
+    // Starts a timed action; the simulation skips the robot until it is over
+    pub fn begin_action(&mut self, action: Action) {
+        let remaining = action.duration().saturating_sub(1);
+        self.action = (remaining > 0).then_some(ActionInProgress { action, remaining });
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.action.is_some()
+    }
+
+    // Spends one tick on the current action
+    pub fn advance_action(&mut self) {
+        if let Some(progress) = &mut self.action {
+            progress.remaining -= 1;
+            if progress.remaining == 0 {
+                self.action = None;
+            }
         }
     }
 
+    pub fn is_broken(&self) -> bool {
+        self.state == RobotState::Broken
+    }
+
+    pub fn is_shut_down(&self) -> bool {
+        self.state == RobotState::ShutDown
+    }
+
+    // Broken and shut-down robots do nothing until they are repaired or switched back on
+    pub fn is_out_of_service(&self) -> bool {
+        self.is_broken() || self.is_shut_down() || self.is_wreck()
+    }
+
+    // A shut-down robot stays where it is and is neither towed nor repaired
+    pub fn shut_down(&mut self, reason: &'static str) {
+        self.set_state(RobotState::ShutDown, reason);
+        self.stop();
+    }
+
+    // A broken robot stops acting until another robot tows it home for repair
+    pub fn break_down(&mut self, reason: &'static str) {
+        self.set_state(RobotState::Broken, reason);
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        self.current_path.clear();
+        self.path_target = None;
+        self.towing = None;
+        self.action = None;
+    }
+
+    fn drain_battery(&mut self, amount: u32) {
+        self.battery = self.battery.saturating_sub(amount);
+    }
+
+    // Mines the deposit under the robot at its extraction rate, keeping only what fits in the cargo
+    pub fn mine(&mut self, map: &mut Map) -> usize {
+        let cell = map.grid[self.y][self.x];
+        let mut mined = 0;
+        while mined < extraction_rate(cell) && self.inventory.can_carry(cell) {
+            let Some(unit) = map.extract(self.x, self.y) else { break };
+            self.inventory.add(unit);
+            mined += 1;
+        }
+        mined
+    }
+
     pub fn turn_left(&mut self) {
         self.direction = match self.direction {
             Direction::North => Direction::West,
@@ -71,7 +261,16 @@ impl Robot {
         };
     }
 
+    // Obstacles block everyone, other terrain only the roles that cannot cross it
+    pub fn can_enter(&self, map: &Map, x: usize, y: usize) -> bool {
+        map.grid[y][x] != Cell::Obstacle && !self.impassable.contains(&map.terrain_at(x, y))
+    }
+
     pub fn move_forward(&mut self, map: &Map) {
+        if self.is_wreck() {
+            return;
+        }
+
         let (new_x, new_y) = match self.direction {
             Direction::North if self.y > 0 => (self.x, self.y - 1),
             Direction::South if self.y < map.height - 1 => (self.x, self.y + 1),
@@ -80,13 +279,17 @@ impl Robot {
             _ => (self.x, self.y),
         };
 
-        if map.grid[new_y][new_x] != Cell::Obstacle {
+        if (new_x, new_y) != (self.x, self.y) && self.can_enter(map, new_x, new_y) {
             self.x = new_x;
             self.y = new_y;
+            self.drain_battery(MOVE_ENERGY_COST);
+            self.move_debt += map.move_cost(new_x, new_y).saturating_sub(1) * ACTION_POINTS;
         }
     }
 
     pub fn vision(&mut self, map: &Map, range: usize, station: &mut crate::station::Station) {
+        self.drain_battery(SENSE_ENERGY_COST);
+
         let min_x = self.x.saturating_sub(range);
         let max_x = usize::min(self.x + range, map.width - 1);
         let min_y = self.y.saturating_sub(range);
@@ -103,37 +306,88 @@ impl Robot {
         }
     }
 
-    pub fn act(&mut self, map: &mut Map, station_x: usize, station_y: usize, station: &mut crate::station::Station) {
-        self.vision(map, 2, station);
+    pub fn act(&mut self, map: &mut Map, station_x: usize, station_y: usize, station: &mut crate::station::Station, registry: &RoleRegistry) {
+        let Some(definition) = registry.get(self.role) else {
+            println!("{}: role {:?} is not registered, skipping", self.label(), self.role);
+            return;
+        };
+        self.inventory.set_capacity(definition.capacity + self.upgrades.capacity_bonus());
+        self.impassable.clone_from(&definition.impassable);
 
-        match self.role {
-            RobotRole::Explorer => self.act_as_explorer(map, station_x, station_y, station),
-            RobotRole::Collector => self.act_as_collector(map, station_x, station_y, station),
-            RobotRole::Scientist => self.act_as_scientist(map, station_x, station_y, station),
+        if self.is_broken() || self.is_shut_down() {
+            return;
         }
+
+        // Refit and recharge happen once per tick in `Simulation::step`, not on every action
+        if self.x == station_x && self.y == station_y && matches!(self.state, RobotState::ReturningToRecharge | RobotState::Recharging) {
+            if !self.inventory.is_empty() {
+                station.receive_resources(self.inventory.drain());
+            }
+            self.report_research(station);
+            if self.battery < self.battery_capacity() {
+                self.set_state(RobotState::Recharging, "docked at the station");
+                return;
+            }
+            self.set_state(RobotState::Idle, "battery full");
+        }
+
+        if self.is_wreck() {
+            self.set_state(RobotState::Wreck, "battery empty");
+            return;
+        }
+
+        if self.state != RobotState::ReturningToRecharge && self.needs_recharge(map, station_x, station_y) {
+            println!("{} low on battery ({}), returning to station", self.label(), self.battery);
+            self.set_state(RobotState::ReturningToRecharge, "battery low");
+        }
+
+        self.vision(map, definition.vision_range + self.upgrades.vision_bonus(), station);
+
+        if self.state == RobotState::ReturningToRecharge {
+            self.move_dijkstra_to(map, station_x, station_y);
+            return;
+        }
+
+        if self.towing.is_some() {
+            self.set_state(RobotState::Towing, "towing a broken robot");
+            self.move_dijkstra_to(map, station_x, station_y);
+            return;
+        }
+
+        definition.behavior.act(self, definition, map, station_x, station_y, station);
     }
 
 
-    fn find_nearest_resource_position(&self, map: &Map) -> Option<(usize, usize)> {
+    // Battery needed to sense and move all the way back to the station, plus a safety margin
+    fn needs_recharge(&self, map: &Map, station_x: usize, station_y: usize) -> bool {
+        if self.x == station_x && self.y == station_y {
+            return false;
+        }
+        let steps = self.distance_to(map, station_x, station_y)
+            .unwrap_or(self.x.abs_diff(station_x) + self.y.abs_diff(station_y));
+        let cost_home = steps as u32 * (MOVE_ENERGY_COST + SENSE_ENERGY_COST) + BATTERY_SAFETY_MARGIN;
+        self.battery <= cost_home
+    }
+
+    // Length of the shortest obstacle-free path to the target, if any
+    fn distance_to(&self, map: &Map, target_x: usize, target_y: usize) -> Option<usize> {
         let mut queue = VecDeque::new();
         let mut visited = HashSet::new();
-        queue.push_back((self.x, self.y));
+        queue.push_back(((self.x, self.y), 0));
         visited.insert((self.x, self.y));
 
-        while let Some((x, y)) = queue.pop_front() {
-            let cell = map.grid[y][x];
-            if cell == Cell::Mineral || cell == Cell::Energy {
-                return Some((x, y));
+        while let Some(((x, y), dist)) = queue.pop_front() {
+            if (x, y) == (target_x, target_y) {
+                return Some(dist);
             }
 
-            // Add neighbors to queue
             for (dx, dy) in &[(0, 1), (1, 0), (0, -1), (-1, 0)] {
                 let nx = x as isize + dx;
                 let ny = y as isize + dy;
                 if nx >= 0 && ny >= 0 && nx < map.width as isize && ny < map.height as isize {
                     let pos = (nx as usize, ny as usize);
-                    if !visited.contains(&pos) && map.grid[pos.1][pos.0] != Cell::Obstacle {
-                        queue.push_back(pos);
+                    if !visited.contains(&pos) && self.can_enter(map, pos.0, pos.1) {
+                        queue.push_back((pos, dist + 1));
                         visited.insert(pos);
                     }
                 }
@@ -142,7 +396,52 @@ impl Robot {
         None
     }
 
-    fn find_resource_position(&self, map: &Map, target_type: Cell) -> Option<(usize, usize)> {
+    // Movement cost from this robot to every cell it can reach, weighted by terrain
+    pub fn path_costs(&self, map: &Map) -> PathCosts {
+        self.weighted_search(map, |_| false).0
+    }
+
+    // Dijkstra over terrain costs, stopping at the first settled position that matches `goal`
+    fn weighted_search(&self, map: &Map, goal: impl Fn((usize, usize)) -> bool) -> (PathCosts, Trail, Option<(usize, usize)>) {
+        let mut costs = HashMap::new();
+        let mut came_from = HashMap::new();
+        let mut heap = BinaryHeap::new();
+        costs.insert((self.x, self.y), 0);
+        heap.push(Reverse((0, (self.x, self.y))));
+
+        while let Some(Reverse((dist, (x, y)))) = heap.pop() {
+            if dist > costs[&(x, y)] {
+                continue;
+            }
+            if goal((x, y)) {
+                return (costs, came_from, Some((x, y)));
+            }
+            for (dx, dy) in &[(0, 1), (1, 0), (0, -1), (-1, 0)] {
+                let nx = x as isize + dx;
+                let ny = y as isize + dy;
+                if nx >= 0 && ny >= 0 && nx < map.width as isize && ny < map.height as isize {
+                    let pos = (nx as usize, ny as usize);
+                    if !self.can_enter(map, pos.0, pos.1) {
+                        continue;
+                    }
+                    let next = dist + map.move_cost(pos.0, pos.1) as usize;
+                    if costs.get(&pos).is_none_or(|&known| next < known) {
+                        costs.insert(pos, next);
+                        came_from.insert(pos, (x, y));
+                        heap.push(Reverse((next, pos)));
+                    }
+                }
+            }
+        }
+        (costs, came_from, None)
+    }
+
+    // Cheapest position to reach, by terrain cost, that matches `wanted`
+    pub fn find_nearest_position(&self, map: &Map, wanted: impl Fn((usize, usize), Cell) -> bool) -> Option<(usize, usize)> {
+        self.weighted_search(map, |(x, y)| wanted((x, y), map.grid[y][x])).2
+    }
+
+    pub fn find_resource_position(&self, map: &Map, target_type: Cell) -> Option<(usize, usize)> {
         for y in 0..map.height {
             for x in 0..map.width {
                 if map.grid[y][x] == target_type {
@@ -153,7 +452,7 @@ impl Robot {
         None
     }
     
-    fn move_random(&mut self, map: &Map) {
+    pub fn move_random(&mut self, map: &Map) {
         let mut rng = rand::thread_rng();
         let dir = rng.gen_range(0..4);
         self.direction = match dir {
@@ -165,7 +464,7 @@ impl Robot {
         self.move_forward(map);
     }
 
-    fn move_smart_towards_unknown(&mut self, map: &Map) {
+    pub fn move_smart_towards_unknown(&mut self, map: &Map) {
         let mut queue = VecDeque::new();
         let mut visited = HashSet::new();
         let mut came_from = vec![vec![None; map.width]; map.height];
@@ -190,7 +489,7 @@ impl Robot {
                     (nx as usize) < map.width && (ny as usize) < map.height {
                     let ux = nx as usize;
                     let uy = ny as usize;
-                    if !visited.contains(&(ux, uy)) && map.grid[uy][ux] != Cell::Obstacle {
+                    if !visited.contains(&(ux, uy)) && self.can_enter(map, ux, uy) {
                         visited.insert((ux, uy));
                         came_from[uy][ux] = Some((cx, cy));
                         queue.push_back((ux, uy));
@@ -214,7 +513,7 @@ impl Robot {
     
             path.reverse();
     
-            if let Some(&(nx, ny)) = path.get(0) {
+            if let Some(&(nx, ny)) = path.first() {
                 if nx > self.x {
                     self.direction = Direction::East;
                 } else if nx < self.x {
@@ -233,117 +532,57 @@ impl Robot {
         self.move_random(map);
     }
     
-    fn move_dijkstra_to(&mut self, map: &mut Map, target_x: usize, target_y: usize) {
-        
-        // If we already have a path, follow it
-        if !self.current_path.is_empty() {
-            if let Some(&(nx, ny)) = self.current_path.first() {
-                if nx > self.x {
-                    self.direction = Direction::East;
-                } else if nx < self.x {
-                    self.direction = Direction::West;
-                } else if ny > self.y {
-                    self.direction = Direction::South;
-                } else if ny < self.y {
-                    self.direction = Direction::North;
-                }
-                self.move_forward(map);
-                if self.x == nx && self.y == ny {
-                    self.current_path.remove(0);
-                }
-                return;
-            }
+    pub fn move_dijkstra_to(&mut self, map: &mut Map, target_x: usize, target_y: usize) {
+        // Drop a cached path that leads somewhere else
+        if self.path_target != Some((target_x, target_y)) {
+            self.current_path.clear();
+            self.path_target = Some((target_x, target_y));
         }
-
-        // Calculate new path
-        let mut queue = VecDeque::new();
-        let mut visited = HashSet::new();
-        let mut came_from = vec![vec![None; map.width]; map.height];
-
-        queue.push_back((self.x, self.y));
-        visited.insert((self.x, self.y));
-
-        while let Some((cx, cy)) = queue.pop_front() {
-            if (cx, cy) == (target_x, target_y) {
-                let mut path = vec![(cx, cy)];
-                let mut current = came_from[cy][cx];
-
-                while let Some((nx, ny)) = current {
-                    path.push((nx, ny));
-                    current = came_from[ny][nx];
-                }
-
-                path.reverse();
-                // Remove our current position from the path
-                if !path.is_empty() && path[0] == (self.x, self.y) {
-                    path.remove(0);
-                }
-                self.current_path = path;
-                break;
-            }
-
-            for (dx, dy) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
-                let nx = cx as isize + dx;
-                let ny = cy as isize + dy;
-
-                if nx >= 0 && ny >= 0 &&
-                    (nx as usize) < map.width && (ny as usize) < map.height {
-                    let ux = nx as usize;
-                    let uy = ny as usize;
-                    if !visited.contains(&(ux, uy)) && map.grid[uy][ux] != Cell::Obstacle {
-                        visited.insert((ux, uy));
-                        came_from[uy][ux] = Some((cx, cy));
-                        queue.push_back((ux, uy));
-                    }
-                }
-            }
+        if (target_x, target_y) == (self.x, self.y) || self.unreachable.contains(&(target_x, target_y)) {
+            return;
         }
 
-        // If we can't find a path, try to move in the general direction
-        if self.current_path.is_empty() {
-            println!("No path found, moving towards target");
-            if target_x > self.x {
+        // If we already have a path, follow it
+        if let Some(&(nx, ny)) = self.current_path.first() {
+            if nx > self.x {
                 self.direction = Direction::East;
-            } else if target_x < self.x {
+            } else if nx < self.x {
                 self.direction = Direction::West;
-            } else if target_y > self.y {
+            } else if ny > self.y {
                 self.direction = Direction::South;
-            } else if target_y < self.y {
+            } else if ny < self.y {
                 self.direction = Direction::North;
             }
             self.move_forward(map);
-        }
-    }
-
-    fn find_nearest_scientist_position(&self, map: &Map) -> Option<(usize, usize)> {
-        let mut queue = VecDeque::new();
-        let mut visited = HashSet::new();
-        queue.push_back((self.x, self.y));
-        visited.insert((self.x, self.y));
-
-        while let Some((x, y)) = queue.pop_front() {
-            let cell = map.grid[y][x];
-            if cell == Cell::Science {
-                return Some((x, y));
+            if self.x == nx && self.y == ny {
+                self.current_path.remove(0);
             }
+            return;
+        }
 
-            // Add neighbors to queue
-            for (dx, dy) in &[(0, 1), (1, 0), (0, -1), (-1, 0)] {
-                let nx = x as isize + dx;
-                let ny = y as isize + dy;
-                if nx >= 0 && ny >= 0 && nx < map.width as isize && ny < map.height as isize {
-                    let pos = (nx as usize, ny as usize);
-                    if !visited.contains(&pos) && map.grid[pos.1][pos.0] != Cell::Obstacle {
-                        queue.push_back(pos);
-                        visited.insert(pos);
-                    }
+        // Calculate new path, preferring cheap terrain over short detours through slow ground
+        let (_, came_from, _) = self.weighted_search(map, |position| position == (target_x, target_y));
+        if came_from.contains_key(&(target_x, target_y)) {
+            let mut path = vec![(target_x, target_y)];
+            let mut current = (target_x, target_y);
+            while let Some(&previous) = came_from.get(&current) {
+                if previous == (self.x, self.y) {
+                    break;
                 }
+                path.push(previous);
+                current = previous;
             }
+            path.reverse();
+            self.current_path = path;
+        }
+
+        // Obstacles and terrain never change during a run, so a target without a path stays out of reach
+        if self.current_path.is_empty() && self.unreachable.insert((target_x, target_y)) {
+            println!("{} has no path to ({}, {}), giving up on it", self.label(), target_x, target_y);
         }
-        None
     }
 
-    fn move_smart_towards_unknown_with_others(&mut self, map: &Map, other_explorers: &[(usize, usize)]) {
+    pub fn move_smart_towards_unknown_with_others(&mut self, map: &Map, other_explorers: &[(usize, usize)]) {
         // If there are no other explorers or we're the only one, use regular exploration
         if other_explorers.len() <= 1 {
             self.move_smart_towards_unknown(map);
@@ -351,9 +590,7 @@ impl Robot {
         }
         
         // Assign each explorer a preferred direction to encourage spreading
-        // Use the robot's memory address as a stable identifier for consistent behavior
-        let robot_id = self as *const _ as usize;
-        let preferred_direction = match robot_id % 4 {
+        let preferred_direction = match self.id.0 % 4 {
             0 => (1, 0),   // East
             1 => (0, 1),   // South
             2 => (-1, 0),  // West
@@ -378,15 +615,19 @@ impl Robot {
             let influence_radius = 15;
             
             // Add higher costs in a radius around other explorers
-            for y in ey.saturating_sub(influence_radius)..=(ey + influence_radius).min(map.height - 1) {
-                for x in ex.saturating_sub(influence_radius)..=(ex + influence_radius).min(map.width - 1) {
+            let min_y = ey.saturating_sub(influence_radius);
+            let max_y = (ey + influence_radius).min(map.height - 1);
+            let min_x = ex.saturating_sub(influence_radius);
+            let max_x = (ex + influence_radius).min(map.width - 1);
+            for (y, row) in cost_map.iter_mut().enumerate().take(max_y + 1).skip(min_y) {
+                for (x, cost) in row.iter_mut().enumerate().take(max_x + 1).skip(min_x) {
                     // Calculate Manhattan distance
                     let distance = (x as isize - ex as isize).abs() + (y as isize - ey as isize).abs();
                     
                     if distance < influence_radius as isize {
                         // Inverse relationship: closer = MUCH higher cost
-                        let additional_cost = (influence_radius as usize - distance as usize) * 10;
-                        cost_map[y][x] += additional_cost; // Much stronger effect
+                        let additional_cost = (influence_radius - distance as usize) * 10;
+                        *cost += additional_cost; // Much stronger effect
                     }
                 }
             }
@@ -396,8 +637,8 @@ impl Robot {
         let center_x = map.width / 2;
         let center_y = map.height / 2;
         
-        for y in 0..map.height {
-            for x in 0..map.width {
+        for (y, row) in cost_map.iter_mut().enumerate() {
+            for (x, cost) in row.iter_mut().enumerate() {
                 // Calculate vector from center
                 let dx = (x as isize - center_x as isize) as f32;
                 let dy = (y as isize - center_y as isize) as f32;
@@ -413,7 +654,7 @@ impl Robot {
                 // Adjust cost based on alignment with preferred direction
                 // Reduce cost if aligned with preferred direction
                 let direction_factor = (1.0 - dot_product) * 5.0; // Scale factor
-                cost_map[y][x] = cost_map[y][x].saturating_add(direction_factor as usize);
+                *cost = cost.saturating_add(direction_factor as usize);
             }
         }
         
@@ -458,7 +699,7 @@ impl Robot {
                 if nx >= 0 && ny >= 0 && (nx as usize) < map.width && (ny as usize) < map.height {
                     let ux = nx as usize;
                     let uy = ny as usize;
-                    if !visited.contains(&(ux, uy)) && map.grid[uy][ux] != Cell::Obstacle {
+                    if !visited.contains(&(ux, uy)) && self.can_enter(map, ux, uy) {
                         visited.insert((ux, uy));
                         came_from[uy][ux] = Some((cx, cy));
                         queue.push_back((ux, uy));
@@ -482,7 +723,7 @@ impl Robot {
 
         path.reverse();
 
-            if let Some(&(nx, ny)) = path.get(0) {
+            if let Some(&(nx, ny)) = path.first() {
                 if nx > self.x {
                 self.direction = Direction::East;
                 } else if nx < self.x {
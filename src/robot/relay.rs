@@ -0,0 +1,37 @@
+use crate::map::Map;
+use crate::station::Station;
+use crate::robot::{Robot, RobotBehavior, RoleDefinition, RobotRole, RobotState};
+use crate::config::RELAY_SPACING;
+
+pub struct RelayBehavior;
+
+impl RobotBehavior for RelayBehavior {
+    fn act(&self, robot: &mut Robot, _role: &RoleDefinition, map: &mut Map, station_x: usize, station_y: usize, station: &mut Station) {
+        robot.act_as_relay(map, station_x, station_y, station);
+    }
+}
+
+impl Robot {
+    // Drives out to the edge of the covered area and parks there, its wide vision keeping the station map up to date
+    pub fn act_as_relay(&mut self, map: &mut Map, station_x: usize, station_y: usize, station: &mut Station) {
+        if matches!(self.state, RobotState::Parked { .. }) {
+            return;
+        }
+
+        if self.is_relay_spot(station_x, station_y, station) {
+            self.set_state(RobotState::Parked { x: self.x, y: self.y }, "far enough from the station and other relays");
+            return;
+        }
+
+        self.set_state(RobotState::Exploring, "looking for a relay spot");
+        self.move_smart_towards_unknown(map);
+    }
+
+    fn is_relay_spot(&self, station_x: usize, station_y: usize, station: &Station) -> bool {
+        let far_from = |(x, y): (usize, usize)| self.x.abs_diff(x) + self.y.abs_diff(y) >= RELAY_SPACING;
+        far_from((station_x, station_y))
+            && station.fleet.iter()
+                .filter(|entry| entry.role == RobotRole::Relay && matches!(entry.state, RobotState::Parked { .. }) && entry.id != self.id)
+                .all(|entry| far_from(entry.position))
+    }
+}
@@ -0,0 +1,140 @@
+use crate::map::{Map, Cell, Terrain};
+use crate::station::Station;
+use crate::robot::{Robot, RobotRole, ExplorerBehavior, CollectorBehavior, ScientistBehavior, HaulerBehavior, BuilderBehavior, RelayBehavior};
+use crate::config::{EXPLORER_RECIPE, COLLECTOR_RECIPE, SCIENTIST_RECIPE, HAULER_RECIPE, BUILDER_RECIPE, RELAY_RECIPE};
+use crate::config::{COLLECTOR_CAPACITY, SCIENTIST_CAPACITY, HAULER_CAPACITY, BUILDER_CAPACITY, RELAY_VISION_RANGE};
+use crate::config::{EXPLORER_SPEED, COLLECTOR_SPEED, SCIENTIST_SPEED, HAULER_SPEED, BUILDER_SPEED, RELAY_SPEED};
+use crate::config::{EXPLORER_BUILD_TIME, COLLECTOR_BUILD_TIME, SCIENTIST_BUILD_TIME, HAULER_BUILD_TIME, BUILDER_BUILD_TIME, RELAY_BUILD_TIME};
+
+// What a robot does each tick, once the battery and vision steps are done
+pub trait RobotBehavior: Send + Sync {
+    fn act(
+        &self,
+        robot: &mut Robot,
+        role: &RoleDefinition,
+        map: &mut Map,
+        station_x: usize,
+        station_y: usize,
+        station: &mut Station,
+    );
+}
+
+pub struct RoleDefinition {
+    pub name: &'static str,
+    pub color: [f32; 3],          // RGB colour of the robot sprite
+    pub symbol: char,             // Letter drawn on the console map
+    pub cost: Vec<(Cell, usize)>, // Resources spent by the station to build one
+    pub build_time: u32,          // Ticks the station spends building one
+    pub vision_range: usize,
+    pub capacity: usize,          // Cargo space, see `Inventory`
+    pub speed: u32,               // Speed points gained per tick, see `ACTION_POINTS`
+    pub impassable: Vec<Terrain>, // Terrain the role cannot enter
+    pub behavior: Box<dyn RobotBehavior>,
+}
+
+// Roles known to the simulation, in production priority order
+pub struct RoleRegistry {
+    roles: Vec<(RobotRole, RoleDefinition)>,
+}
+
+impl RoleRegistry {
+    pub fn new() -> Self {
+        Self { roles: Vec::new() }
+    }
+
+    // Registering an existing role replaces its definition
+    pub fn register(&mut self, role: RobotRole, definition: RoleDefinition) {
+        if let Some(entry) = self.roles.iter_mut().find(|(r, _)| *r == role) {
+            entry.1 = definition;
+        } else {
+            self.roles.push((role, definition));
+        }
+    }
+
+    pub fn get(&self, role: RobotRole) -> Option<&RoleDefinition> {
+        self.roles.iter().find(|(r, _)| *r == role).map(|(_, def)| def)
+    }
+
+    pub fn roles(&self) -> impl Iterator<Item = (RobotRole, &RoleDefinition)> {
+        self.roles.iter().map(|(role, def)| (*role, def))
+    }
+}
+
+impl Default for RoleRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(RobotRole::Explorer, RoleDefinition {
+            name: "Explorer",
+            color: [0.0, 1.0, 0.0], // Green
+            symbol: 'e',
+            cost: EXPLORER_RECIPE.to_vec(),
+            build_time: EXPLORER_BUILD_TIME,
+            vision_range: 2,
+            capacity: 0,
+            speed: EXPLORER_SPEED,
+            impassable: Vec::new(),
+            behavior: Box::new(ExplorerBehavior),
+        });
+        registry.register(RobotRole::Collector, RoleDefinition {
+            name: "Collector",
+            color: [1.0, 0.5, 0.0], // Orange
+            symbol: 'c',
+            cost: COLLECTOR_RECIPE.to_vec(),
+            build_time: COLLECTOR_BUILD_TIME,
+            vision_range: 2,
+            capacity: COLLECTOR_CAPACITY,
+            speed: COLLECTOR_SPEED,
+            impassable: vec![Terrain::Water],
+            behavior: Box::new(CollectorBehavior),
+        });
+        registry.register(RobotRole::Scientist, RoleDefinition {
+            name: "Scientist",
+            color: [0.8, 0.0, 0.8], // Purple
+            symbol: 's',
+            cost: SCIENTIST_RECIPE.to_vec(),
+            build_time: SCIENTIST_BUILD_TIME,
+            vision_range: 2,
+            capacity: SCIENTIST_CAPACITY,
+            speed: SCIENTIST_SPEED,
+            impassable: vec![Terrain::Water],
+            behavior: Box::new(ScientistBehavior),
+        });
+        registry.register(RobotRole::Hauler, RoleDefinition {
+            name: "Hauler",
+            color: [0.55, 0.35, 0.15], // Brown
+            symbol: 'h',
+            cost: HAULER_RECIPE.to_vec(),
+            build_time: HAULER_BUILD_TIME,
+            vision_range: 2,
+            capacity: HAULER_CAPACITY,
+            speed: HAULER_SPEED,
+            impassable: vec![Terrain::Water, Terrain::Rock],
+            behavior: Box::new(HaulerBehavior),
+        });
+        registry.register(RobotRole::Builder, RoleDefinition {
+            name: "Builder",
+            color: [1.0, 1.0, 0.0], // Yellow
+            symbol: 'b',
+            cost: BUILDER_RECIPE.to_vec(),
+            build_time: BUILDER_BUILD_TIME,
+            vision_range: 2,
+            capacity: BUILDER_CAPACITY,
+            speed: BUILDER_SPEED,
+            impassable: vec![Terrain::Water],
+            behavior: Box::new(BuilderBehavior),
+        });
+        registry.register(RobotRole::Relay, RoleDefinition {
+            name: "Relay",
+            color: [0.0, 0.4, 1.0], // Blue
+            symbol: 'r',
+            cost: RELAY_RECIPE.to_vec(),
+            build_time: RELAY_BUILD_TIME,
+            vision_range: RELAY_VISION_RANGE,
+            capacity: 0,
+            speed: RELAY_SPEED,
+            impassable: Vec::new(),
+            behavior: Box::new(RelayBehavior),
+        });
+        registry
+    }
+}
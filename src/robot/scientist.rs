@@ -1,31 +1,85 @@
 use crate::map::{Map, Cell};
 use crate::station::Station;
-use crate::robot::Robot;
+use crate::robot::{Robot, RobotBehavior, RoleDefinition, RobotState, Action};
+use crate::config::ANALYSIS_RADIUS;
+
+pub struct ScientistBehavior;
+
+impl RobotBehavior for ScientistBehavior {
+    fn act(&self, robot: &mut Robot, _role: &RoleDefinition, map: &mut Map, station_x: usize, station_y: usize, station: &mut Station) {
+        robot.act_as_scientist(map, station_x, station_y, station);
+    }
+}
+
+// Outcome of analysing a science deposit: every deposit within the radius, explored or not
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResearchResult {
+    pub site: (usize, usize),
+    pub radius: usize,
+    pub deposits: Vec<((usize, usize), Cell, usize)>, // Position, resource and units left
+}
 
 impl Robot {
     pub fn act_as_scientist(&mut self, map: &mut Map, station_x: usize, station_y: usize, station: &mut Station) {
-        self.vision(map, 2, station);
-
-        let current_cell = map.grid[self.y][self.x];
-        if current_cell == Cell::Science && self.collected.len() < 1 {
-            self.collected.push(current_cell);
-            map.grid[self.y][self.x] = Cell::Empty;
-            println!("Scientist collected a science resource! Total collected: {}", self.collected.len());
+        let site = (self.x, self.y);
+        if map.grid[self.y][self.x] == Cell::Science && !self.is_analysed(site, station) && self.inventory.can_carry(Cell::Science) {
+            let result = self.analyse(map, ANALYSIS_RADIUS);
+            self.mine(map);
+            self.set_state(RobotState::Analysing, "standing on an unanalysed science deposit");
+            self.begin_action(Action::Analysis);
+            println!("{} analysed the deposit at ({}, {}): {} deposits found nearby", self.label(), site.0, site.1, result.deposits.len());
+            self.research_results.push(result);
+            return;
         }
 
-        if self.collected.len() >= 1 {
+        if self.inventory.is_full() {
             if self.x == station_x && self.y == station_y {
-                println!("Scientist depositing {} science resources at station", self.collected.len());
-                station.receive_resources(self.collected.drain(..).collect());
+                self.set_state(RobotState::Depositing, "results at the station");
+                println!("{} reporting {} analyses at station", self.label(), self.research_results.len());
+                station.receive_resources(self.inventory.drain());
+                self.report_research(station);
+                self.begin_action(Action::Deposit);
             } else {
+                self.set_state(RobotState::ReturningToStation, "sample bay full");
                 self.move_dijkstra_to(map, station_x, station_y);
             }
+        } else if let Some((target_x, target_y)) = self.find_nearest_scientist_position(map, station) {
+            self.set_state(RobotState::TravellingToResource { x: target_x, y: target_y }, "nearest unanalysed science deposit");
+            self.move_dijkstra_to(map, target_x, target_y);
         } else {
-            if let Some((target_x, target_y)) = self.find_nearest_scientist_position(map) {
-                self.move_dijkstra_to(map, target_x, target_y);
-            } else {
-                self.move_random(map);
+            self.set_state(RobotState::Searching, "no unanalysed science deposit known");
+            self.move_random(map);
+        }
+    }
+
+    // Surveys the area around the robot, reading deposits even where nobody has looked yet
+    pub fn analyse(&self, map: &Map, radius: usize) -> ResearchResult {
+        let mut deposits = Vec::new();
+        for y in self.y.saturating_sub(radius)..=usize::min(self.y + radius, map.height - 1) {
+            for x in self.x.saturating_sub(radius)..=usize::min(self.x + radius, map.width - 1) {
+                let cell = map.grid[y][x];
+                if cell.is_resource() {
+                    deposits.push(((x, y), cell, map.quantity(x, y)));
+                }
             }
         }
+        ResearchResult { site: (self.x, self.y), radius, deposits }
+    }
+
+    // Analysed by this robot on its current trip, or already reported to the station
+    fn is_analysed(&self, site: (usize, usize), station: &Station) -> bool {
+        station.is_analysed(site.0, site.1) || self.research_results.iter().any(|result| result.site == site)
+    }
+
+    pub fn report_research(&mut self, station: &mut Station) {
+        if !self.research_results.is_empty() {
+            station.receive_research(std::mem::take(&mut self.research_results));
+        }
+    }
+
+    pub fn find_nearest_scientist_position(&self, map: &Map, station: &Station) -> Option<(usize, usize)> {
+        self.find_nearest_position(map, |position, cell| {
+            cell == Cell::Science && self.inventory.can_carry(cell) && !self.is_analysed(position, station)
+        })
     }
 }
@@ -0,0 +1,62 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RobotState {
+    Idle,
+    Exploring,
+    Searching,                                   // No known target, wandering to find one
+    TravellingToResource { x: usize, y: usize },
+    Collecting,
+    MeetingCollector { x: usize, y: usize },     // Hauler heading to a loaded collector in the field
+    TravellingToSite { x: usize, y: usize },     // Builder heading to where it will build
+    Building { x: usize, y: usize },
+    Parked { x: usize, y: usize },               // Relay holding its position
+    Analysing,                                   // Scientist surveying a science deposit
+    ReturningToStation,                          // Cargo full, heading home to unload
+    Depositing,
+    ReturningToRecharge,
+    Recharging,
+    Towing,                                      // Bringing a broken robot back to the station
+    Broken,                                      // Out of order until repaired at the station
+    ShutDown,                                    // Switched off until the station pays its upkeep debt
+    Wreck,
+}
+
+impl fmt::Display for RobotState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RobotState::Idle => write!(f, "Idle"),
+            RobotState::Exploring => write!(f, "Exploring"),
+            RobotState::Searching => write!(f, "Searching"),
+            RobotState::TravellingToResource { x, y } => write!(f, "Travelling to resource ({}, {})", x, y),
+            RobotState::Collecting => write!(f, "Collecting"),
+            RobotState::MeetingCollector { x, y } => write!(f, "Meeting collector at ({}, {})", x, y),
+            RobotState::TravellingToSite { x, y } => write!(f, "Travelling to build site ({}, {})", x, y),
+            RobotState::Building { x, y } => write!(f, "Building at ({}, {})", x, y),
+            RobotState::Parked { x, y } => write!(f, "Parked at ({}, {})", x, y),
+            RobotState::Analysing => write!(f, "Analysing"),
+            RobotState::ReturningToStation => write!(f, "Returning to station"),
+            RobotState::Depositing => write!(f, "Depositing"),
+            RobotState::ReturningToRecharge => write!(f, "Returning to recharge"),
+            RobotState::Recharging => write!(f, "Recharging"),
+            RobotState::Towing => write!(f, "Towing"),
+            RobotState::Broken => write!(f, "Broken"),
+            RobotState::ShutDown => write!(f, "Shut down"),
+            RobotState::Wreck => write!(f, "Wreck"),
+        }
+    }
+}
+
+// Event emitted every time a robot changes state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateTransition {
+    pub from: RobotState,
+    pub to: RobotState,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for StateTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {} ({})", self.from, self.to, self.reason)
+    }
+}
@@ -0,0 +1,85 @@
+use crate::config::{SENSOR_RANGE_PER_LEVEL, CARRY_CAPACITY_PER_LEVEL, BATTERY_PER_LEVEL, SPEED_PER_LEVEL};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Upgrade {
+    SensorRange,
+    CarryCapacity,
+    Battery,
+    Speed,
+}
+
+impl Upgrade {
+    // Research order used when several upgrades share the lowest level
+    pub const ALL: [Upgrade; 4] = [Upgrade::SensorRange, Upgrade::CarryCapacity, Upgrade::Battery, Upgrade::Speed];
+}
+
+impl fmt::Display for Upgrade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Upgrade::SensorRange => write!(f, "Sensor range"),
+            Upgrade::CarryCapacity => write!(f, "Carry capacity"),
+            Upgrade::Battery => write!(f, "Battery"),
+            Upgrade::Speed => write!(f, "Speed"),
+        }
+    }
+}
+
+// Level reached in each upgrade, 0 meaning stock hardware
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Upgrades {
+    pub sensor_range: u32,
+    pub carry_capacity: u32,
+    pub battery: u32,
+    pub speed: u32,
+}
+
+impl Upgrades {
+    pub fn level(&self, upgrade: Upgrade) -> u32 {
+        match upgrade {
+            Upgrade::SensorRange => self.sensor_range,
+            Upgrade::CarryCapacity => self.carry_capacity,
+            Upgrade::Battery => self.battery,
+            Upgrade::Speed => self.speed,
+        }
+    }
+
+    pub fn level_mut(&mut self, upgrade: Upgrade) -> &mut u32 {
+        match upgrade {
+            Upgrade::SensorRange => &mut self.sensor_range,
+            Upgrade::CarryCapacity => &mut self.carry_capacity,
+            Upgrade::Battery => &mut self.battery,
+            Upgrade::Speed => &mut self.speed,
+        }
+    }
+
+    // Brings every upgrade up to at least the levels of `other`
+    pub fn merge(&mut self, other: &Upgrades) {
+        for upgrade in Upgrade::ALL {
+            let level = self.level_mut(upgrade);
+            *level = (*level).max(other.level(upgrade));
+        }
+    }
+
+    pub fn vision_bonus(&self) -> usize {
+        self.sensor_range as usize * SENSOR_RANGE_PER_LEVEL
+    }
+
+    pub fn capacity_bonus(&self) -> usize {
+        self.carry_capacity as usize * CARRY_CAPACITY_PER_LEVEL
+    }
+
+    pub fn battery_bonus(&self) -> u32 {
+        self.battery * BATTERY_PER_LEVEL
+    }
+
+    pub fn speed_bonus(&self) -> u32 {
+        self.speed * SPEED_PER_LEVEL
+    }
+}
+
+impl fmt::Display for Upgrades {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sensor {}, cargo {}, battery {}, speed {}", self.sensor_range, self.carry_capacity, self.battery, self.speed)
+    }
+}
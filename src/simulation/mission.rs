@@ -0,0 +1,100 @@
+use crate::map::Cell;
+use crate::config::{MISSION_OBJECTIVES, MISSION_TIME_LIMIT};
+use std::collections::HashMap;
+use std::fmt;
+
+// Goal checked every tick; the mission succeeds once all of them hold at the same time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Objective {
+    Explore(f32),         // Share of the cells reachable from the station known to the station
+    Deliver(Cell, usize), // Units of a resource delivered to the stations since the start
+    Survive(u64),         // Ticks the swarm must keep at least one robot working
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Objective::Explore(share) => write!(f, "explore {:.0}% of reachable cells", share * 100.0),
+            Objective::Deliver(cell, amount) => write!(f, "deliver {} {:?}", amount, cell),
+            Objective::Survive(ticks) => write!(f, "survive {} ticks", ticks),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Success,
+    Failure,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Success => write!(f, "success"),
+            Outcome::Failure => write!(f, "failure"),
+        }
+    }
+}
+
+// How and when a run ended
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissionResult {
+    pub outcome: Outcome,
+    pub tick: u64,
+    pub reason: String,
+}
+
+impl fmt::Display for MissionResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at tick {}: {}", self.outcome, self.tick, self.reason)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mission {
+    pub objectives: Vec<Objective>,
+    pub time_limit: Option<u64>, // The mission fails if the objectives are not met by then
+}
+
+impl Default for Mission {
+    fn default() -> Self {
+        Self { objectives: MISSION_OBJECTIVES.to_vec(), time_limit: Some(MISSION_TIME_LIMIT) }
+    }
+}
+
+// Everything the objectives are checked against, gathered by the simulation each tick
+pub struct MissionProgress<'a> {
+    pub tick: u64,
+    pub explored: f32, // Share of reachable cells known to the station
+    pub delivered: &'a HashMap<Cell, usize>,
+    pub robots: usize,
+    pub working_robots: usize,
+    pub queued_robots: usize, // Orders in the production queue, robots still to come
+}
+
+impl Mission {
+    pub fn is_met(&self, objective: Objective, progress: &MissionProgress) -> bool {
+        match objective {
+            Objective::Explore(share) => progress.explored >= share,
+            Objective::Deliver(cell, amount) => progress.delivered.get(&cell).copied().unwrap_or(0) >= amount,
+            Objective::Survive(ticks) => progress.tick >= ticks && progress.working_robots > 0,
+        }
+    }
+
+    // Ends the run on success, on the loss of every robot or once the time limit is reached
+    pub fn evaluate(&self, progress: &MissionProgress) -> Option<MissionResult> {
+        let end = |outcome, reason: String| Some(MissionResult { outcome, tick: progress.tick, reason });
+
+        if !self.objectives.is_empty() && self.objectives.iter().all(|&objective| self.is_met(objective, progress)) {
+            let met: Vec<String> = self.objectives.iter().map(|objective| objective.to_string()).collect();
+            return end(Outcome::Success, format!("objectives met ({})", met.join(", ")));
+        }
+        if progress.robots > 0 && progress.working_robots == 0 && progress.queued_robots == 0 {
+            return end(Outcome::Failure, "no working robot left".to_string());
+        }
+        if self.time_limit.is_some_and(|limit| progress.tick >= limit) {
+            return end(Outcome::Failure, "time limit reached".to_string());
+        }
+        None
+    }
+}
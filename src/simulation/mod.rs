@@ -0,0 +1,564 @@
+pub mod mission;
+
+use crate::map::{Map, Cell, Structure};
+use crate::robot::{Robot, RobotId, RobotRole, RoleRegistry, RobotState, StateTransition, Upgrade};
+use crate::station::{Station, logic};
+use crate::station::tasks::{Allocation, Bid};
+use mission::{Mission, MissionProgress, MissionResult, Outcome};
+use crate::map::connectivity::reachable_cells;
+use crate::station::policy::{ProductionPolicy, ProductionContext, policy_from_config};
+use crate::config::{SEED, FAILURE_PROBABILITY, ACTION_POINTS, MAX_OUTPOSTS, OUTPOST_RECIPE, OUTPOST_SPACING, SHARED_INVENTORY};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimulationEvent {
+    RobotCreated { tick: u64, robot: RobotId, role: RobotRole },
+    StateChanged { tick: u64, robot: RobotId, transition: StateTransition },
+    UpgradeResearched { tick: u64, upgrade: Upgrade, level: u32 },
+    RobotOrdered { tick: u64, role: RobotRole },
+    OutpostFounded { tick: u64, position: (usize, usize) },
+    MissionEnded { tick: u64, outcome: Outcome },
+}
+
+impl fmt::Display for SimulationEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationEvent::RobotCreated { tick, robot, role } => {
+                write!(f, "[tick {}] robot {} created as {}", tick, robot, role)
+            }
+            SimulationEvent::StateChanged { tick, robot, transition } => {
+                write!(f, "[tick {}] robot {}: {}", tick, robot, transition)
+            }
+            SimulationEvent::UpgradeResearched { tick, upgrade, level } => {
+                write!(f, "[tick {}] {} upgraded to level {}", tick, upgrade, level)
+            }
+            SimulationEvent::RobotOrdered { tick, role } => {
+                write!(f, "[tick {}] {} robot queued for production", tick, role)
+            }
+            SimulationEvent::OutpostFounded { tick, position } => {
+                write!(f, "[tick {}] outpost founded at ({}, {})", tick, position.0, position.1)
+            }
+            SimulationEvent::MissionEnded { tick, outcome } => {
+                write!(f, "[tick {}] mission ended: {}", tick, outcome)
+            }
+        }
+    }
+}
+
+// Owns the whole world and advances it one tick at a time
+pub struct Simulation {
+    pub map: Map,
+    pub robots: Vec<Robot>,
+    pub station: Station,             // Headquarters, where robots are built and research is pooled
+    pub outposts: Vec<Station>,       // Stations founded during play
+    pub shared_inventory: bool,       // Outposts use the headquarters stock rather than their own
+    pub registry: RoleRegistry,
+    pub tick: u64,
+    pub events: Vec<SimulationEvent>, // Everything that happened since the start
+    pub failure_probability: f64,     // Chance per tick that a working robot breaks down
+    pub policy: Box<dyn ProductionPolicy>,
+    pub mission: Mission,
+    pub result: Option<MissionResult>, // Set once the mission is over; later steps do nothing
+    reachable: HashSet<(usize, usize)>, // Cells reachable from the headquarters, for the exploration objective
+    next_robot_id: u32,
+    rng: StdRng,                      // Seeded so that breakdowns are reproducible
+}
+
+impl Simulation {
+    pub fn new(map: Map, station: Station, registry: RoleRegistry) -> Self {
+        let reachable = reachable_cells(&map, (station.x, station.y));
+        Self {
+            map,
+            robots: Vec::new(),
+            station,
+            outposts: Vec::new(),
+            shared_inventory: SHARED_INVENTORY,
+            registry,
+            tick: 0,
+            events: Vec::new(),
+            failure_probability: FAILURE_PROBABILITY,
+            policy: policy_from_config(),
+            mission: Mission::default(),
+            result: None,
+            reachable,
+            next_robot_id: 1,
+            rng: StdRng::seed_from_u64(SEED),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: Box<dyn ProductionPolicy>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_allocation(mut self, allocation: Allocation) -> Self {
+        self.station.tasks.allocation = allocation;
+        self
+    }
+
+    pub fn with_mission(mut self, mission: Mission) -> Self {
+        self.mission = mission;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    // Adds a robot under a fresh identifier, ignoring whatever id it carried before
+    pub fn add_robot(&mut self, mut robot: Robot) -> RobotId {
+        let id = RobotId(self.next_robot_id);
+        self.next_robot_id += 1;
+        robot.id = id;
+        self.robots.push(robot);
+        id
+    }
+
+    pub fn remove_robot(&mut self, id: RobotId) -> Option<Robot> {
+        let index = self.robots.iter().position(|robot| robot.id == id)?;
+        Some(self.robots.remove(index))
+    }
+
+    // Opens an outpost on an empty cell, sharing everything the headquarters knows
+    pub fn add_outpost(&mut self, x: usize, y: usize) -> bool {
+        if x >= self.map.width || y >= self.map.height || self.station_positions().contains(&(x, y)) || !self.map.build(x, y, Structure::Outpost) {
+            return false;
+        }
+        let mut outpost = Station::new(x, y);
+        outpost.discovered = self.station.discovered.clone();
+        outpost.known_deposits = self.station.known_deposits.clone();
+        outpost.research_results = self.station.research_results.clone();
+        outpost.technology = self.station.technology;
+        self.outposts.push(outpost);
+        println!("Outpost founded at ({}, {})", x, y);
+        true
+    }
+
+    // Headquarters first, then the outposts in founding order
+    pub fn station_positions(&self) -> Vec<(usize, usize)> {
+        std::iter::once(&self.station).chain(&self.outposts).map(|station| (station.x, station.y)).collect()
+    }
+
+    pub fn robot(&self, id: RobotId) -> Option<&Robot> {
+        self.robots.iter().find(|robot| robot.id == id)
+    }
+
+    pub fn robot_mut(&mut self, id: RobotId) -> Option<&mut Robot> {
+        self.robots.iter_mut().find(|robot| robot.id == id)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.result.is_some()
+    }
+
+    // Share of the cells reachable from the headquarters that the station knows about
+    pub fn explored_share(&self) -> f32 {
+        let known = self.reachable.iter().filter(|position| self.station.discovered.contains_key(position)).count();
+        known as f32 / self.reachable.len().max(1) as f32
+    }
+
+    // Runs one tick and returns the events it produced; does nothing once the mission is over
+    pub fn step(&mut self) -> Vec<SimulationEvent> {
+        if self.is_finished() {
+            return Vec::new();
+        }
+        self.tick += 1;
+        let mut events = Vec::new();
+
+        let robot_refs: Vec<&Robot> = self.robots.iter().collect();
+        for station in std::iter::once(&mut self.station).chain(&mut self.outposts) {
+            station.update_explorer_positions(&robot_refs);
+            station.update_fleet(&robot_refs);
+        }
+
+        self.station.tasks.refresh(self.tick, &self.map, &self.station.discovered);
+        if self.station.tasks.allocation == Allocation::Auction {
+            self.run_auction();
+        }
+        // Outposts with their own stock work from a copy of the task board, merged back after the robots act
+        let claims = self.station.tasks.claims.clone();
+        for outpost in &mut self.outposts {
+            outpost.tasks = self.station.tasks.clone();
+        }
+        self.roll_failures();
+
+        let stations = self.station_positions();
+        for robot in &mut self.robots {
+            // Robots busy with a timed action sit this tick out
+            if robot.is_busy() {
+                robot.advance_action();
+                continue;
+            }
+
+            // Docked robots are refitted and recharged once per tick, however many actions they get
+            if let Some(index) = stations.iter().position(|&position| position == (robot.x, robot.y))
+                && !robot.is_broken()
+                && !robot.is_shut_down()
+            {
+                let station = station_mut(&mut self.station, &mut self.outposts, index, self.shared_inventory);
+                station.refit_robot(robot);
+                station.recharge_robot(robot);
+            }
+
+            // Speed points buy actions: fast robots act several times, slow ones skip ticks,
+            // and the debt left by slow terrain is paid before the next action
+            robot.speed_points += self.registry.get(robot.role).map_or(ACTION_POINTS, |definition| robot.speed(definition));
+            while robot.speed_points >= ACTION_POINTS + robot.move_debt && !robot.is_busy() {
+                robot.speed_points -= ACTION_POINTS + robot.move_debt;
+                robot.move_debt = 0;
+                let home = nearest_station(robot, &self.map, &stations);
+                let (station_x, station_y) = stations[home];
+                let station = station_mut(&mut self.station, &mut self.outposts, home, self.shared_inventory);
+                robot.act(&mut self.map, station_x, station_y, station, &self.registry);
+            }
+            if robot.is_busy() {
+                robot.speed_points = 0;
+            }
+        }
+
+        for outpost in &self.outposts {
+            self.station.tasks.merge_claims(&claims, &outpost.tasks);
+        }
+        self.transfer_cargo();
+        self.reveal_around_structures();
+        self.merge_stations();
+        self.update_towing();
+        for robot in &mut self.robots {
+            if let Some(index) = stations.iter().position(|&position| position == (robot.x, robot.y)) {
+                station_mut(&mut self.station, &mut self.outposts, index, self.shared_inventory).repair_robot(robot);
+            }
+        }
+
+        self.charge_upkeep();
+
+        for robot in &mut self.robots {
+            for transition in robot.take_transitions() {
+                events.push(SimulationEvent::StateChanged { tick: self.tick, robot: robot.id, transition });
+            }
+        }
+
+        if let Some(upgrade) = self.station.maybe_research_upgrade() {
+            let level = self.station.technology.level(upgrade);
+            events.push(SimulationEvent::UpgradeResearched { tick: self.tick, upgrade, level });
+        }
+
+        if let Some(position) = self.maybe_found_outpost() {
+            events.push(SimulationEvent::OutpostFounded { tick: self.tick, position });
+        }
+        if let Some(role) = self.order_robot() {
+            events.push(SimulationEvent::RobotOrdered { tick: self.tick, role });
+        }
+        if let Some(new_robot) = self.station.advance_production() {
+            let role = new_robot.role;
+            let id = self.add_robot(new_robot);
+            events.push(SimulationEvent::RobotCreated { tick: self.tick, robot: id, role });
+        }
+
+        if let Some(result) = self.evaluate_mission() {
+            println!("Mission over: {}", result);
+            events.push(SimulationEvent::MissionEnded { tick: self.tick, outcome: result.outcome });
+            self.result = Some(result);
+        }
+
+        self.events.extend(events.iter().cloned());
+        events
+    }
+
+    fn evaluate_mission(&self) -> Option<MissionResult> {
+        let working_robots = self.robots.iter().filter(|robot| !robot.is_out_of_service()).count();
+        let mut delivered = self.station.delivered.clone();
+        for outpost in &self.outposts {
+            for (&cell, &amount) in &outpost.delivered {
+                *delivered.entry(cell).or_insert(0) += amount;
+            }
+        }
+        self.mission.evaluate(&MissionProgress {
+            tick: self.tick,
+            explored: self.explored_share(),
+            delivered: &delivered,
+            robots: self.robots.len(),
+            working_robots,
+            queued_robots: self.station.production_queue.len(),
+        })
+    }
+
+    pub fn production_context(&self) -> ProductionContext<'_> {
+        production_context(&self.map, &self.robots, &self.station, &self.registry, self.explored_share())
+    }
+
+    // Asks the production policy for a role and queues it once the station can afford it
+    fn order_robot(&mut self) -> Option<RobotRole> {
+        let context = production_context(&self.map, &self.robots, &self.station, &self.registry, self.explored_share());
+        let role = self.policy.next_role(&context)?;
+        if !self.station.enqueue(role, &self.registry) {
+            return None;
+        }
+        self.policy.on_ordered(role);
+        Some(role)
+    }
+
+    // Idle collectors bid on the announced tasks they can reach and carry
+    fn run_auction(&mut self) {
+        let open = self.station.tasks.announce();
+        if open.is_empty() {
+            return;
+        }
+
+        let mut bids = Vec::new();
+        for robot in &self.robots {
+            let idle = robot.role == RobotRole::Collector
+                && !robot.is_out_of_service()
+                && robot.state != RobotState::ReturningToRecharge
+                && robot.inventory.free_capacity() > 0
+                && self.station.tasks.claimed_by(robot.id).is_none();
+            if !idle {
+                continue;
+            }
+
+            let costs = robot.path_costs(&self.map);
+            for &(position, cell) in &open {
+                if let Some(&path_cost) = costs.get(&position)
+                    && robot.inventory.can_carry(cell)
+                    && robot.target_resource.is_none_or(|target| target == cell)
+                {
+                    bids.push(Bid { robot: robot.id, position, path_cost, free_capacity: robot.inventory.free_capacity() });
+                }
+            }
+        }
+
+        for bid in self.station.tasks.award(bids) {
+            println!("Task at ({}, {}) awarded to robot {} (path cost {}, free capacity {})",
+                bid.position.0, bid.position.1, bid.robot, bid.path_cost, bid.free_capacity);
+        }
+    }
+
+    // Spends the outpost recipe on a site next to known resources that every station is far from
+    fn maybe_found_outpost(&mut self) -> Option<(usize, usize)> {
+        if self.outposts.len() >= MAX_OUTPOSTS || !logic::can_afford(&self.station.resources_collected, OUTPOST_RECIPE) {
+            return None;
+        }
+
+        let stations = self.station_positions();
+        let (hx, hy) = (self.station.x, self.station.y);
+        let mut candidates: Vec<(usize, usize)> = self.station.discovered.keys()
+            .copied()
+            .filter(|&(x, y)| self.map.grid[y][x].is_resource())
+            .filter(|&(x, y)| stations.iter().all(|s| s.0.abs_diff(x) + s.1.abs_diff(y) >= OUTPOST_SPACING))
+            .collect();
+        candidates.sort_by_key(|&(x, y)| (x.abs_diff(hx) + y.abs_diff(hy), x, y));
+
+        for (x, y) in candidates {
+            let free = [(0, 1), (1, 0), (0, -1), (-1, 0)].into_iter()
+                .filter_map(|(dx, dy): (isize, isize)| Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?)))
+                .find(|&(nx, ny)| nx < self.map.width && ny < self.map.height
+                    && self.map.grid[ny][nx] == Cell::Empty
+                    && !self.map.structures.contains_key(&(nx, ny)));
+            if let Some((nx, ny)) = free
+                && self.add_outpost(nx, ny)
+            {
+                self.station.spend(OUTPOST_RECIPE);
+                return Some((nx, ny));
+            }
+        }
+        None
+    }
+
+    // Outposts hand what they learnt over to the headquarters and get the merged picture back
+    fn merge_stations(&mut self) {
+        let headquarters = &mut self.station;
+        for outpost in &mut self.outposts {
+            headquarters.discovered.extend(outpost.discovered.iter().map(|(&position, &cell)| (position, cell)));
+            headquarters.known_deposits.extend(outpost.known_deposits.drain());
+            for result in outpost.research_results.drain(..) {
+                if !headquarters.is_analysed(result.site.0, result.site.1) {
+                    headquarters.research_results.push(result);
+                }
+            }
+            headquarters.scientific_discoveries += std::mem::take(&mut outpost.scientific_discoveries);
+            headquarters.research_points += std::mem::take(&mut outpost.research_points);
+        }
+        for outpost in &mut self.outposts {
+            outpost.discovered = headquarters.discovered.clone();
+            outpost.known_deposits = headquarters.known_deposits.clone();
+            outpost.research_results = headquarters.research_results.clone();
+            outpost.technology = headquarters.technology;
+        }
+    }
+
+    // Robots the station cannot afford to run shut down, the most recent first, and restart once the debt is paid
+    fn charge_upkeep(&mut self) {
+        let working = self.robots.iter().filter(|robot| !robot.is_out_of_service()).count();
+        let over_credit = self.station.charge_upkeep(working);
+        if self.station.upkeep_debt < 1.0 {
+            for robot in self.robots.iter_mut().filter(|robot| robot.is_shut_down()) {
+                robot.set_state(RobotState::Idle, "upkeep paid");
+            }
+        }
+        if !over_credit {
+            return;
+        }
+        if let Some(robot) = self.robots.iter_mut().rev().find(|robot| !robot.is_out_of_service()) {
+            println!("{} shut down, station owes {:.1} energy of upkeep", robot.label(), self.station.upkeep_debt);
+            robot.shut_down("upkeep unpaid");
+        }
+    }
+
+    fn roll_failures(&mut self) {
+        for robot in &mut self.robots {
+            if robot.is_out_of_service() {
+                continue;
+            }
+            if self.rng.gen_bool(self.failure_probability) {
+                println!("{} broke down at ({}, {})", robot.label(), robot.x, robot.y);
+                robot.break_down("random failure");
+            }
+        }
+    }
+
+    // Haulers next to a loaded collector take over as much of its cargo as they can carry
+    fn transfer_cargo(&mut self) {
+        for hauler in 0..self.robots.len() {
+            if self.robots[hauler].role != RobotRole::Hauler || self.robots[hauler].is_out_of_service() {
+                continue;
+            }
+            for collector in 0..self.robots.len() {
+                let (h, c) = (&self.robots[hauler], &self.robots[collector]);
+                if c.role != RobotRole::Collector || c.inventory.is_empty() || h.x.abs_diff(c.x) + h.y.abs_diff(c.y) > 1 {
+                    continue;
+                }
+
+                let mut left_over = Vec::new();
+                let mut moved = 0;
+                for item in self.robots[collector].inventory.drain() {
+                    if self.robots[hauler].inventory.add(item) {
+                        moved += 1;
+                    } else {
+                        left_over.push(item);
+                    }
+                }
+                for item in left_over {
+                    self.robots[collector].inventory.add(item);
+                }
+                if moved > 0 {
+                    println!("{} took over {} resources from {}", self.robots[hauler].label(), moved, self.robots[collector].label());
+                }
+            }
+        }
+    }
+
+    // Structures keep the station map up to date around them
+    fn reveal_around_structures(&mut self) {
+        let structures: Vec<((usize, usize), Structure)> = self.map.structures.iter().map(|(&p, &s)| (p, s)).collect();
+        for ((x, y), structure) in structures {
+            let range = structure.vision_range();
+            for ny in y.saturating_sub(range)..=usize::min(y + range, self.map.height - 1) {
+                for nx in x.saturating_sub(range)..=usize::min(x + range, self.map.width - 1) {
+                    self.station.discovered.insert((nx, ny), self.map.grid[ny][nx]);
+                }
+            }
+        }
+    }
+
+    // Working robots next to a broken one hook it up and drag it back to the nearest station
+    fn update_towing(&mut self) {
+        let stations = self.station_positions();
+
+        for index in 0..self.robots.len() {
+            let broken = &self.robots[index];
+            if !broken.is_broken() || stations.contains(&(broken.x, broken.y)) {
+                continue;
+            }
+            let broken_id = broken.id;
+            let broken_position = (broken.x, broken.y);
+
+            let can_pull = |robot: &Robot| !robot.is_out_of_service();
+            let tower_index = broken.towed_by
+                .and_then(|tower| self.robots.iter().position(|r| r.id == tower && r.towing == Some(broken_id) && can_pull(r)))
+                .or_else(|| self.robots.iter().position(|r| {
+                    r.towing.is_none()
+                        && can_pull(r)
+                        && r.x.abs_diff(broken_position.0) + r.y.abs_diff(broken_position.1) <= 1
+                }));
+            let Some(tower_index) = tower_index else {
+                self.robots[index].towed_by = None;
+                continue;
+            };
+
+            let tower_id = self.robots[tower_index].id;
+            let tower_position = (self.robots[tower_index].x, self.robots[tower_index].y);
+            if self.robots[index].towed_by != Some(tower_id) {
+                println!("{} starts towing {}", self.robots[tower_index].label(), self.robots[index].label());
+            }
+            self.robots[tower_index].towing = Some(broken_id);
+            let broken = &mut self.robots[index];
+            broken.towed_by = Some(tower_id);
+            (broken.x, broken.y) = tower_position;
+
+            // Drop the broken robot off at the station, where it waits for repair
+            if stations.contains(&tower_position) {
+                self.robots[index].towed_by = None;
+                self.robots[tower_index].towing = None;
+                self.robots[tower_index].set_state(RobotState::Idle, "broken robot delivered");
+            }
+        }
+
+        // Towers whose broken robot was dropped, repaired or taken over are free again
+        let pulled: Vec<(RobotId, RobotId)> = self.robots.iter()
+            .filter_map(|robot| robot.towed_by.map(|tower| (tower, robot.id)))
+            .collect();
+        for robot in &mut self.robots {
+            if let Some(towed) = robot.towing
+                && !pulled.contains(&(robot.id, towed))
+            {
+                robot.towing = None;
+            }
+        }
+    }
+}
+
+// Index of the station a robot works with, by path length; 0 is the headquarters
+fn nearest_station(robot: &Robot, map: &Map, stations: &[(usize, usize)]) -> usize {
+    if stations.len() > 1
+        && let Some(position) = robot.find_nearest_position(map, |position, _| stations.contains(&position))
+    {
+        return stations.iter().position(|&station| station == position).unwrap_or(0);
+    }
+    0
+}
+
+// The station a robot deals with: the headquarters when the stock is shared, otherwise the outpost itself
+fn station_mut<'a>(headquarters: &'a mut Station, outposts: &'a mut [Station], index: usize, shared: bool) -> &'a mut Station {
+    if index == 0 || shared { headquarters } else { &mut outposts[index - 1] }
+}
+
+// `explored` is the share of reachable cells the station knows, so walled-off areas do not keep explorers coming
+fn production_context<'a>(map: &Map, robots: &[Robot], station: &'a Station, registry: &'a RoleRegistry, explored: f32) -> ProductionContext<'a> {
+    let mut fleet = HashMap::new();
+    for robot in robots.iter().filter(|robot| !robot.is_wreck()) {
+        *fleet.entry(robot.role).or_insert(0) += 1;
+    }
+    for order in &station.production_queue {
+        *fleet.entry(order.role).or_insert(0) += 1;
+    }
+
+    let still_there = |&(x, y): &(usize, usize), kind: fn(Cell) -> bool| kind(map.grid[y][x]);
+    let known_resources = station.discovered.keys()
+        .filter(|position| still_there(position, |cell| cell == Cell::Mineral || cell == Cell::Energy))
+        .count();
+    let unanalysed_science = station.discovered.keys()
+        .filter(|position| still_there(position, |cell| cell == Cell::Science) && !station.is_analysed(position.0, position.1))
+        .count();
+
+    ProductionContext {
+        registry,
+        resources: &station.resources_collected,
+        fleet,
+        explored,
+        known_resources,
+        unanalysed_science,
+    }
+}
@@ -1,23 +1,23 @@
 use std::collections::HashMap;
 use crate::map::Cell;
-use crate::robot::RobotRole;
-use crate::config::{EXPLORER_COST, COLLECTOR_COST, SCIENTIST_COST};
 
-pub fn can_create_robot(resources: &mut HashMap<Cell, usize>) -> Option<RobotRole> {
-    let energy = resources.get(&Cell::Energy).copied().unwrap_or(0);
-    let mineral = resources.get(&Cell::Mineral).copied().unwrap_or(0);
-    let science = resources.get(&Cell::Science).copied().unwrap_or(0);
+pub fn can_afford(resources: &HashMap<Cell, usize>, cost: &[(Cell, usize)]) -> bool {
+    cost.iter().all(|(cell, amount)| resources.get(cell).copied().unwrap_or(0) >= *amount)
+}
+
+pub fn pay(resources: &mut HashMap<Cell, usize>, cost: &[(Cell, usize)]) {
+    for (cell, amount) in cost {
+        *resources.entry(*cell).or_insert(0) -= amount;
+    }
+}
 
-    if energy >= EXPLORER_COST {
-        *resources.entry(Cell::Energy).or_insert(0) -= EXPLORER_COST;
-        Some(RobotRole::Explorer)
-    } else if mineral >= COLLECTOR_COST {
-        *resources.entry(Cell::Mineral).or_insert(0) -= COLLECTOR_COST;
-        Some(RobotRole::Collector)
-    } else if science >= SCIENTIST_COST {
-        *resources.entry(Cell::Science).or_insert(0) -= SCIENTIST_COST;
-        Some(RobotRole::Scientist)
-    } else {
-        None
+pub fn refund(resources: &mut HashMap<Cell, usize>, cost: &[(Cell, usize)]) {
+    for (cell, amount) in cost {
+        *resources.entry(*cell).or_insert(0) += amount;
     }
 }
+
+// Recipe multiplied by a factor, such as the level of an upgrade
+pub fn scaled(cost: &[(Cell, usize)], factor: usize) -> Vec<(Cell, usize)> {
+    cost.iter().map(|&(cell, amount)| (cell, amount * factor)).collect()
+}
@@ -1,9 +1,37 @@
 pub mod logic;
+pub mod policy;
+pub mod tasks;
 
 use crate::map::Cell;
-use crate::robot::{Robot, RobotRole, Direction};
-use crate::config::{EXPLORER_COST, COLLECTOR_COST, SCIENTIST_COST};
-use std::collections::HashMap;
+use crate::robot::{Robot, RobotId, RobotRole, Direction, RoleRegistry, RobotState, Upgrade, Upgrades, ResearchResult};
+use crate::config::{PRODUCTION_QUEUE_LENGTH, STATION_RECHARGE_RATE, ENERGY_UNIT_CHARGE, REPAIR_MINERAL_COST, RESEARCH_POINTS_PER_SCIENCE, UPGRADE_BASE_COST, UPGRADE_MAX_LEVEL, UPGRADE_RECIPE, STORAGE_CAPACITY, UPKEEP_ENERGY_PER_ROBOT, UPKEEP_CREDIT};
+use tasks::TaskBoard;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+// Snapshot of a robot shared with the whole swarm, refreshed at the start of each tick
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FleetEntry {
+    pub id: RobotId,
+    pub role: RobotRole,
+    pub position: (usize, usize),
+    pub state: RobotState,
+    pub load: usize,
+}
+
+// Robot waiting in the production queue, its cost already taken out of the stock
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductionOrder {
+    pub role: RobotRole,
+    pub reserved: Vec<(Cell, usize)>,
+    pub remaining: u32, // Ticks of work left once the order reaches the head of the queue
+}
+
+impl fmt::Display for ProductionOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({} ticks)", self.role, self.remaining)
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Station {
@@ -13,7 +41,18 @@ pub struct Station {
     pub resources_collected: HashMap<Cell, usize>,
     pub robots_created: usize,
     pub scientific_discoveries: usize,
+    pub research_points: u32,
+    pub technology: Upgrades, // Upgrade levels researched so far, fitted to every docking robot
+    pub research_results: Vec<ResearchResult>,
+    pub known_deposits: HashMap<(usize, usize), (Cell, usize)>, // Latest surveyed quantity of each deposit
     pub explorer_positions: Vec<(usize, usize)>,
+    pub fleet: Vec<FleetEntry>,
+    pub production_queue: VecDeque<ProductionOrder>, // Only the head order is being built
+    pub tasks: TaskBoard,
+    pub storage_capacity: HashMap<Cell, usize>, // Resources missing from the map have no limit
+    pub delivered: HashMap<Cell, usize>,        // Units received since the start, spent or not
+    pub wasted: usize,                          // Units delivered while storage was full
+    pub upkeep_debt: f32,                       // Energy owed for robot upkeep
 }
 
 impl Station {
@@ -25,7 +64,18 @@ impl Station {
             resources_collected: HashMap::new(),
             robots_created: 0,
             scientific_discoveries: 0,
+            research_points: 0,
+            technology: Upgrades::default(),
+            research_results: Vec::new(),
+            known_deposits: HashMap::new(),
             explorer_positions: Vec::new(),
+            fleet: Vec::new(),
+            production_queue: VecDeque::new(),
+            tasks: TaskBoard::default(),
+            storage_capacity: STORAGE_CAPACITY.iter().copied().collect(),
+            delivered: HashMap::new(),
+            wasted: 0,
+            upkeep_debt: 0.0,
         }
     }
 
@@ -38,25 +88,201 @@ impl Station {
         }
     }
 
+    pub fn update_fleet(&mut self, robots: &[&Robot]) {
+        self.fleet = robots.iter()
+            .map(|robot| FleetEntry {
+                id: robot.id,
+                role: robot.role,
+                position: (robot.x, robot.y),
+                state: robot.state,
+                load: robot.inventory.load(),
+            })
+            .collect();
+    }
+
     pub fn get_explorer_positions(&self) -> &[(usize, usize)] {
         &self.explorer_positions
     }
 
     pub fn receive_resources(&mut self, collected_cells: Vec<Cell>) {
         for cell in collected_cells {
-            *self.resources_collected.entry(cell).or_insert(0) += 1;
+            if cell == Cell::Science {
+                self.scientific_discoveries += 1;
+            }
+            *self.delivered.entry(cell).or_insert(0) += 1;
+            let stock = self.resources_collected.entry(cell).or_insert(0);
+            if self.storage_capacity.get(&cell).is_some_and(|&capacity| *stock >= capacity) {
+                self.wasted += 1;
+                continue;
+            }
+            *stock += 1;
+            // Only science that fits in storage can be studied
+            if cell == Cell::Science {
+                self.research_points += RESEARCH_POINTS_PER_SCIENCE;
+            }
+        }
+    }
+
+    // Free room for a resource, None when it is not limited
+    pub fn free_storage(&self, cell: Cell) -> Option<usize> {
+        let stock = self.resources_collected.get(&cell).copied().unwrap_or(0);
+        self.storage_capacity.get(&cell).map(|&capacity| capacity.saturating_sub(stock))
+    }
+
+    // Bills the upkeep of the working robots and pays it from the energy stock;
+    // true once the unpaid part goes over the credit limit
+    pub fn charge_upkeep(&mut self, working_robots: usize) -> bool {
+        self.upkeep_debt += working_robots as f32 * UPKEEP_ENERGY_PER_ROBOT;
+        let stock = self.resources_collected.get(&Cell::Energy).copied().unwrap_or(0);
+        let paid = (self.upkeep_debt.floor() as usize).min(stock);
+        if paid > 0 {
+            logic::pay(&mut self.resources_collected, &[(Cell::Energy, paid)]);
+            self.upkeep_debt -= paid as f32;
+        }
+        self.upkeep_debt > UPKEEP_CREDIT
+    }
+
+    // Deducts a whole recipe from the stock, or nothing if any ingredient is short
+    pub fn spend(&mut self, recipe: &[(Cell, usize)]) -> bool {
+        if !logic::can_afford(&self.resources_collected, recipe) {
+            return false;
+        }
+        logic::pay(&mut self.resources_collected, recipe);
+        true
+    }
+
+    // Reveals the surveyed deposits on the station map, including unexplored areas
+    pub fn receive_research(&mut self, results: Vec<ResearchResult>) {
+        for result in results {
+            for &(position, cell, quantity) in &result.deposits {
+                self.discovered.insert(position, cell);
+                self.known_deposits.insert(position, (cell, quantity));
+            }
+            println!("Station received analysis of ({}, {}): {} deposits revealed", result.site.0, result.site.1, result.deposits.len());
+            self.research_results.push(result);
+        }
+    }
+
+    pub fn is_analysed(&self, x: usize, y: usize) -> bool {
+        self.research_results.iter().any(|result| result.site == (x, y))
+    }
+
+    // Recharge a docked robot, burning stored energy when the battery is far from full
+    pub fn recharge_robot(&mut self, robot: &mut Robot) {
+        let capacity = robot.battery_capacity();
+        if robot.battery >= capacity {
+            return;
+        }
+
+        robot.battery = (robot.battery + STATION_RECHARGE_RATE).min(capacity);
+
+        let energy_stock = self.resources_collected.get(&Cell::Energy).copied().unwrap_or(0);
+        if energy_stock > 0 && capacity - robot.battery >= ENERGY_UNIT_CHARGE {
+            *self.resources_collected.entry(Cell::Energy).or_insert(0) -= 1;
+            robot.battery += ENERGY_UNIT_CHARGE;
         }
     }
 
-    pub fn maybe_create_robot(&mut self) -> Option<Robot> {
-        use logic::can_create_robot;
+    // Repair a broken robot standing on the station, if enough minerals are in stock
+    pub fn repair_robot(&mut self, robot: &mut Robot) -> bool {
+        if !robot.is_broken() || (robot.x, robot.y) != (self.x, self.y) {
+            return false;
+        }
+
+        let mineral_stock = self.resources_collected.entry(Cell::Mineral).or_insert(0);
+        if *mineral_stock < REPAIR_MINERAL_COST {
+            return false;
+        }
+        *mineral_stock -= REPAIR_MINERAL_COST;
+
+        robot.towed_by = None;
+        robot.set_state(RobotState::Idle, "repaired at the station");
+        println!("Station repaired {} for {} minerals", robot.label(), REPAIR_MINERAL_COST);
+        true
+    }
+
+    // Research points needed for the next level of an upgrade, None once maxed out
+    pub fn upgrade_cost(&self, upgrade: Upgrade) -> Option<u32> {
+        let level = self.technology.level(upgrade);
+        (level < UPGRADE_MAX_LEVEL).then(|| UPGRADE_BASE_COST * (level + 1))
+    }
+
+    // Resources needed for the next level of an upgrade, alongside its research points
+    pub fn upgrade_recipe(&self, upgrade: Upgrade) -> Vec<(Cell, usize)> {
+        logic::scaled(UPGRADE_RECIPE, self.technology.level(upgrade) as usize + 1)
+    }
+
+    pub fn research_upgrade(&mut self, upgrade: Upgrade) -> bool {
+        match self.upgrade_cost(upgrade) {
+            Some(cost) if cost <= self.research_points && self.spend(&self.upgrade_recipe(upgrade)) => {
+                self.research_points -= cost;
+                *self.technology.level_mut(upgrade) += 1;
+                println!("Station researched {} level {}", upgrade, self.technology.level(upgrade));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Spends research points on the least advanced upgrade, keeping the fleet balanced
+    pub fn maybe_research_upgrade(&mut self) -> Option<Upgrade> {
+        let upgrade = Upgrade::ALL.into_iter()
+            .filter(|&upgrade| self.upgrade_cost(upgrade).is_some())
+            .min_by_key(|&upgrade| self.technology.level(upgrade))?;
+        self.research_upgrade(upgrade).then_some(upgrade)
+    }
+
+    // Installs the researched upgrades on a robot docked at the station
+    pub fn refit_robot(&self, robot: &mut Robot) {
+        robot.upgrades.merge(&self.technology);
+    }
+
+    // Queues a robot, reserving its cost; refused when the queue is full or the stock too low
+    pub fn enqueue(&mut self, role: RobotRole, registry: &RoleRegistry) -> bool {
+        let Some(definition) = registry.get(role) else { return false };
+        if self.production_queue.len() >= PRODUCTION_QUEUE_LENGTH || !self.spend(&definition.cost) {
+            return false;
+        }
+        self.production_queue.push_back(ProductionOrder {
+            role,
+            reserved: definition.cost.clone(),
+            remaining: definition.build_time,
+        });
+        println!("Station queued a new {} robot", role);
+        true
+    }
+
+    // Cancels an order and gives its reserved resources back
+    pub fn cancel_order(&mut self, index: usize) -> Option<ProductionOrder> {
+        let order = self.production_queue.remove(index)?;
+        logic::refund(&mut self.resources_collected, &order.reserved);
+        Some(order)
+    }
+
+    // Resources held by queued orders
+    pub fn reserved(&self) -> HashMap<Cell, usize> {
+        let mut reserved = HashMap::new();
+        for order in &self.production_queue {
+            for &(cell, amount) in &order.reserved {
+                *reserved.entry(cell).or_insert(0) += amount;
+            }
+        }
+        reserved
+    }
 
-        if let Some(role) = can_create_robot(&mut self.resources_collected) {
-            self.robots_created += 1;
-            println!("Station created a new {:?} robot!", role);
-            return Some(Robot::new(self.x, self.y, Direction::North, role));
+    // Spends one tick on the head order, rolling the robot out once it is done
+    pub fn advance_production(&mut self) -> Option<Robot> {
+        let order = self.production_queue.front_mut()?;
+        order.remaining = order.remaining.saturating_sub(1);
+        if order.remaining > 0 {
+            return None;
         }
 
-        None
+        let order = self.production_queue.pop_front()?;
+        self.robots_created += 1;
+        println!("Station created a new {:?} robot!", order.role);
+        let mut robot = Robot::new(self.x, self.y, Direction::North, order.role);
+        self.refit_robot(&mut robot);
+        Some(robot)
     }
 }
@@ -0,0 +1,125 @@
+use std::collections::{HashMap, VecDeque};
+use crate::map::Cell;
+use crate::robot::{RobotRole, RoleRegistry};
+use crate::station::logic::can_afford;
+use crate::config::{PRODUCTION_POLICY, FIXED_RATIO, MANUAL_QUEUE};
+use crate::config::{EXPLORATION_TARGET, MAX_EXPLORERS, RESOURCES_PER_COLLECTOR, SCIENCE_PER_SCIENTIST, COLLECTORS_PER_HAULER};
+
+// What the station knows when deciding which robot to build next
+pub struct ProductionContext<'a> {
+    pub registry: &'a RoleRegistry,
+    pub resources: &'a HashMap<Cell, usize>,
+    pub fleet: HashMap<RobotRole, usize>, // Working and queued robots per role
+    pub explored: f32,                    // Share of the reachable map known to the station, from 0.0 to 1.0
+    pub known_resources: usize,           // Known Mineral and Energy deposits still on the map
+    pub unanalysed_science: usize,        // Known science deposits nobody has analysed yet
+}
+
+impl ProductionContext<'_> {
+    pub fn count(&self, role: RobotRole) -> usize {
+        self.fleet.get(&role).copied().unwrap_or(0)
+    }
+}
+
+// Decides which robot the station builds next; the station waits until it can afford it
+pub trait ProductionPolicy: Send + Sync {
+    fn next_role(&mut self, context: &ProductionContext) -> Option<RobotRole>;
+
+    // Called once the station has accepted an order for the role
+    fn on_ordered(&mut self, _role: RobotRole) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyKind {
+    FirstAffordable,
+    FixedRatio,
+    DemandDriven,
+    ManualQueue,
+}
+
+// Builds the policy selected in `config/mod.rs`
+pub fn policy_from_config() -> Box<dyn ProductionPolicy> {
+    match PRODUCTION_POLICY {
+        PolicyKind::FirstAffordable => Box::new(FirstAffordablePolicy),
+        PolicyKind::FixedRatio => Box::new(FixedRatioPolicy::new(FIXED_RATIO.to_vec())),
+        PolicyKind::DemandDriven => Box::new(DemandDrivenPolicy),
+        PolicyKind::ManualQueue => Box::new(ManualQueuePolicy::new(MANUAL_QUEUE.iter().copied())),
+    }
+}
+
+// Original behaviour: the first registered role the station can afford
+pub struct FirstAffordablePolicy;
+
+impl ProductionPolicy for FirstAffordablePolicy {
+    fn next_role(&mut self, context: &ProductionContext) -> Option<RobotRole> {
+        context.registry.roles()
+            .find(|(_, definition)| !definition.cost.is_empty() && can_afford(context.resources, &definition.cost))
+            .map(|(role, _)| role)
+    }
+}
+
+// Keeps the fleet close to a target mix, e.g. two collectors for each explorer
+pub struct FixedRatioPolicy {
+    ratios: Vec<(RobotRole, usize)>,
+}
+
+impl FixedRatioPolicy {
+    pub fn new(ratios: Vec<(RobotRole, usize)>) -> Self {
+        Self { ratios }
+    }
+}
+
+impl ProductionPolicy for FixedRatioPolicy {
+    fn next_role(&mut self, context: &ProductionContext) -> Option<RobotRole> {
+        // The role furthest below its share, compared as count / weight without floats
+        self.ratios.iter()
+            .filter(|(_, weight)| *weight > 0)
+            .min_by(|(a, wa), (b, wb)| (context.count(*a) * wb).cmp(&(context.count(*b) * wa)))
+            .map(|(role, _)| *role)
+    }
+}
+
+// Builds whatever the current state of the map calls for, or nothing
+pub struct DemandDrivenPolicy;
+
+impl ProductionPolicy for DemandDrivenPolicy {
+    fn next_role(&mut self, context: &ProductionContext) -> Option<RobotRole> {
+        let collectors = context.count(RobotRole::Collector);
+        if context.known_resources > collectors * RESOURCES_PER_COLLECTOR {
+            return Some(RobotRole::Collector);
+        }
+        if context.explored < EXPLORATION_TARGET && context.count(RobotRole::Explorer) < MAX_EXPLORERS {
+            return Some(RobotRole::Explorer);
+        }
+        if context.unanalysed_science > context.count(RobotRole::Scientist) * SCIENCE_PER_SCIENTIST {
+            return Some(RobotRole::Scientist);
+        }
+        if collectors > context.count(RobotRole::Hauler) * COLLECTORS_PER_HAULER {
+            return Some(RobotRole::Hauler);
+        }
+        None
+    }
+}
+
+// Builds exactly the robots queued by the operator, in order
+pub struct ManualQueuePolicy {
+    pub queue: VecDeque<RobotRole>,
+}
+
+impl ManualQueuePolicy {
+    pub fn new(roles: impl IntoIterator<Item = RobotRole>) -> Self {
+        Self { queue: roles.into_iter().collect() }
+    }
+}
+
+impl ProductionPolicy for ManualQueuePolicy {
+    fn next_role(&mut self, _context: &ProductionContext) -> Option<RobotRole> {
+        self.queue.front().copied()
+    }
+
+    fn on_ordered(&mut self, role: RobotRole) {
+        if self.queue.front() == Some(&role) {
+            self.queue.pop_front();
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::map::{Map, Cell};
+use crate::robot::RobotId;
+use crate::config::{CLAIM_DURATION, TASK_ALLOCATION};
+use std::collections::HashMap;
+
+// How collectors end up on a resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Allocation {
+    Greedy,  // Each collector claims the nearest free resource itself
+    Auction, // The station announces open tasks and awards each one to the lowest bid
+}
+
+// A collector's offer for a task, based on how far it is and how much it can bring back
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bid {
+    pub robot: RobotId,
+    pub position: (usize, usize),
+    pub path_cost: usize,
+    pub free_capacity: usize,
+}
+
+impl Bid {
+    // Path cost per unit of cargo space, the lower the better
+    pub fn score(&self) -> f32 {
+        self.path_cost as f32 / self.free_capacity.max(1) as f32
+    }
+}
+
+// A robot's hold on a resource, dropped unless renewed before it expires
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Claim {
+    pub robot: RobotId,
+    pub expires: u64,
+}
+
+// Known resources waiting to be collected, and which robot is going for each of them
+#[derive(Debug, Clone)]
+pub struct TaskBoard {
+    pub tasks: HashMap<(usize, usize), Cell>,
+    pub claims: HashMap<(usize, usize), Claim>,
+    pub now: u64,
+    pub allocation: Allocation,
+}
+
+impl Default for TaskBoard {
+    fn default() -> Self {
+        Self { tasks: HashMap::new(), claims: HashMap::new(), now: 0, allocation: TASK_ALLOCATION }
+    }
+}
+
+impl TaskBoard {
+    // Posts the known resources still on the map, forgetting finished tasks and stale claims
+    pub fn refresh(&mut self, now: u64, map: &Map, known: &HashMap<(usize, usize), Cell>) {
+        self.now = now;
+        self.tasks = known.keys()
+            .map(|&(x, y)| ((x, y), map.grid[y][x]))
+            .filter(|&(_, cell)| cell == Cell::Mineral || cell == Cell::Energy)
+            .collect();
+        self.claims.retain(|&(x, y), claim| claim.expires > now && map.grid[y][x].is_resource());
+    }
+
+    pub fn is_available(&self, position: (usize, usize), robot: RobotId) -> bool {
+        self.claims.get(&position).is_none_or(|claim| claim.robot == robot || claim.expires <= self.now)
+    }
+
+    // Takes or renews a claim; a robot holds at most one at a time
+    pub fn claim(&mut self, position: (usize, usize), robot: RobotId) -> bool {
+        if !self.is_available(position, robot) {
+            return false;
+        }
+        self.release(robot);
+        self.claims.insert(position, Claim { robot, expires: self.now + CLAIM_DURATION });
+        true
+    }
+
+    pub fn release(&mut self, robot: RobotId) {
+        self.claims.retain(|_, claim| claim.robot != robot);
+    }
+
+    pub fn claimed_by(&self, robot: RobotId) -> Option<(usize, usize)> {
+        self.claims.iter().find(|(_, claim)| claim.robot == robot).map(|(&position, _)| position)
+    }
+
+    // Applies the claims another board took or dropped since it was copied from `base`
+    pub fn merge_claims(&mut self, base: &HashMap<(usize, usize), Claim>, other: &TaskBoard) {
+        for (position, claim) in base {
+            if other.claims.get(position) != Some(claim) && self.claims.get(position) == Some(claim) {
+                self.claims.remove(position);
+            }
+        }
+        for (&position, &claim) in &other.claims {
+            if base.get(&position) != Some(&claim) {
+                self.release(claim.robot);
+                self.claims.insert(position, claim);
+            }
+        }
+    }
+
+    // Posted tasks nobody is working on
+    pub fn open_tasks(&self) -> usize {
+        self.tasks.keys().filter(|position| !self.claims.contains_key(position)).count()
+    }
+
+    // Tasks announced for auction, in a stable order
+    pub fn announce(&self) -> Vec<((usize, usize), Cell)> {
+        let mut open: Vec<((usize, usize), Cell)> = self.tasks.iter()
+            .filter(|(position, _)| !self.claims.contains_key(position))
+            .map(|(&position, &cell)| (position, cell))
+            .collect();
+        open.sort_by_key(|&(position, _)| position);
+        open
+    }
+
+    // Awards tasks from the lowest bid up, one task per robot and one robot per task
+    pub fn award(&mut self, mut bids: Vec<Bid>) -> Vec<Bid> {
+        bids.sort_by(|a, b| a.score().total_cmp(&b.score())
+            .then(a.robot.cmp(&b.robot))
+            .then(a.position.cmp(&b.position)));
+
+        let mut won: Vec<Bid> = Vec::new();
+        for bid in bids {
+            if won.iter().any(|winner| winner.robot == bid.robot || winner.position == bid.position) {
+                continue;
+            }
+            if self.claim(bid.position, bid.robot) {
+                won.push(bid);
+            }
+        }
+        won
+    }
+}
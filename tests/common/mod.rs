@@ -0,0 +1,26 @@
+// Fixtures shared by the integration tests; each test crate uses only some of them
+#![allow(dead_code)]
+
+use projet_essaim::map::{Map, Cell};
+use projet_essaim::robot::{RobotBehavior, RoleDefinition};
+
+// Custom role with a single action per tick, no cargo, no cost and a one-tick build
+pub fn custom_role(name: &'static str, symbol: char, behavior: impl RobotBehavior + 'static) -> RoleDefinition {
+    RoleDefinition {
+        name,
+        color: [1.0, 1.0, 1.0],
+        symbol,
+        cost: Vec::new(),
+        build_time: 1,
+        vision_range: 1,
+        capacity: 0,
+        speed: 10,
+        impassable: Vec::new(),
+        behavior: Box::new(behavior),
+    }
+}
+
+// Obstacle-free map on plain ground with no resources
+pub fn open_map(width: usize, height: usize) -> Map {
+    Map::from_cells(vec![vec![Cell::Empty; width]; height])
+}
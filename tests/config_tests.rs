@@ -1,6 +1,7 @@
 use projet_essaim::config::{MAP_WIDTH, MAP_HEIGHT, FOG_OF_WAR};
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn test_constants_are_valid() {
     assert!(MAP_WIDTH > 0);
     assert!(MAP_HEIGHT > 0);
@@ -1,8 +1,18 @@
-use projet_essaim::map::{Map, Cell};
+use projet_essaim::map::{Map, Cell, Deposit, MapGenerator, Terrain, Structure, Connectivity, MapFile, MapFileError, MapError};
+use projet_essaim::robot::{Robot, Direction, RoleRegistry, RobotRole};
+use projet_essaim::map::connectivity::{components, reachable_cells};
+use projet_essaim::map::generator::{PerlinGenerator, CaveGenerator, RoomsGenerator, IslandsGenerator, MazeGenerator};
+use projet_essaim::config::{PERLIN_SCALE, PERLIN_THRESHOLD};
+use projet_essaim::map::placement::{place_resources, ResourceLayout, ClusterShape};
+use rand::{SeedableRng, rngs::StdRng};
+use projet_essaim::config::{DEPOSIT_MIN_QUANTITY, DEPOSIT_MAX_QUANTITY};
+
+mod common;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::open_map;
 
     #[test]
     fn test_map_creation() {
@@ -25,4 +35,345 @@ mod tests {
             }
         }
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_resource_cells_have_deposits() {
+        let map = Map::new(20, 20, 42);
+        for (y, row) in map.grid.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if cell.is_resource() {
+                    let quantity = map.quantity(x, y);
+                    assert!((DEPOSIT_MIN_QUANTITY..=DEPOSIT_MAX_QUANTITY).contains(&quantity));
+                } else {
+                    assert_eq!(map.quantity(x, y), 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_extract_depletes_deposit() {
+        let mut map = Map::new(10, 10, 42);
+        map.grid[0][0] = Cell::Mineral;
+        map.terrain[0][0] = Terrain::Sand;
+        map.deposits.insert((0, 0), Deposit::new(2));
+
+        assert_eq!(map.extract(0, 0), Some(Cell::Mineral));
+        assert_eq!(map.quantity(0, 0), 1);
+        assert_eq!(map.deposits[&(0, 0)].depletion(), 0.5);
+
+        assert_eq!(map.extract(0, 0), Some(Cell::Mineral));
+        assert_eq!(map.grid[0][0], Cell::Empty);
+        assert_eq!(map.terrain_at(0, 0), Terrain::Sand);
+        assert_eq!(map.extract(0, 0), None);
+    }
+
+    #[test]
+    fn test_default_map_uses_configured_perlin_generator() {
+        let perlin = PerlinGenerator { scale: PERLIN_SCALE, threshold: PERLIN_THRESHOLD };
+        let map = Map::generate(20, 20, 7, &perlin);
+        assert_eq!(map.grid, Map::new(20, 20, 7).grid);
+    }
+
+    #[test]
+    fn test_generators_are_deterministic_and_sized() {
+        let generators: Vec<Box<dyn MapGenerator>> = vec![
+            Box::new(CaveGenerator { fill: 0.45, iterations: 4 }),
+            Box::new(RoomsGenerator { rooms: 5, min_size: 3, max_size: 6 }),
+            Box::new(IslandsGenerator { islands: 3, radius: 0.2 }),
+            Box::new(MazeGenerator),
+        ];
+        for generator in &generators {
+            let grid = generator.generate(21, 15, 3);
+            assert_eq!(grid.len(), 15);
+            assert!(grid.iter().all(|row| row.len() == 21));
+            assert_eq!(grid, generator.generate(21, 15, 3));
+            assert!(grid.iter().flatten().any(|&cell| cell == Cell::Empty));
+            assert!(grid.iter().flatten().any(|&cell| cell == Cell::Obstacle));
+        }
+    }
+
+    #[test]
+    fn test_maze_corridors_sit_on_odd_cells() {
+        let grid = MazeGenerator.generate(11, 11, 5);
+        for y in (1..11).step_by(2) {
+            for x in (1..11).step_by(2) {
+                assert_eq!(grid[y][x], Cell::Empty);
+            }
+        }
+        assert!((0..11).all(|i| grid[0][i] == Cell::Obstacle && grid[i][0] == Cell::Obstacle));
+    }
+
+    fn count(grid: &[Vec<Cell>], kind: Cell) -> usize {
+        grid.iter().flatten().filter(|&&cell| cell == kind).count()
+    }
+
+    // Resource cells with at least one orthogonal neighbour of the same resource
+    fn clustered(grid: &[Vec<Cell>], kind: Cell) -> usize {
+        let mut total = 0;
+        for y in 0..grid.len() {
+            for x in 0..grid[y].len() {
+                let touching = [(0, 1), (1, 0), (0, -1), (-1, 0)].iter().any(|&(dx, dy): &(isize, isize)| {
+                    let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else { return false };
+                    grid.get(ny).and_then(|row| row.get(nx)) == Some(&kind)
+                });
+                if grid[y][x] == kind && touching {
+                    total += 1;
+                }
+            }
+        }
+        total
+    }
+
+    #[test]
+    fn test_resources_reach_their_density_in_clusters() {
+        let mut grid = vec![vec![Cell::Empty; 30]; 30];
+        let layouts = [
+            ResourceLayout { cell: Cell::Energy, density: 0.05, cluster_size: 9, shape: ClusterShape::Field },
+            ResourceLayout { cell: Cell::Mineral, density: 0.05, cluster_size: 9, shape: ClusterShape::Vein },
+            ResourceLayout { cell: Cell::Science, density: 0.02, cluster_size: 9, shape: ClusterShape::Scattered },
+        ];
+        place_resources(&mut grid, &layouts, &mut StdRng::seed_from_u64(1));
+
+        assert_eq!(count(&grid, Cell::Energy), 45);
+        assert_eq!(count(&grid, Cell::Mineral), 45);
+        assert_eq!(count(&grid, Cell::Science), 18);
+        assert!(clustered(&grid, Cell::Energy) > 40);
+        assert!(clustered(&grid, Cell::Mineral) > 40);
+    }
+
+    #[test]
+    fn test_placement_stops_when_the_map_is_full() {
+        let mut grid = vec![vec![Cell::Obstacle; 4]; 4];
+        grid[0][0] = Cell::Empty;
+        grid[0][1] = Cell::Empty;
+        let layouts = [ResourceLayout { cell: Cell::Mineral, density: 0.5, cluster_size: 4, shape: ClusterShape::Vein }];
+        place_resources(&mut grid, &layouts, &mut StdRng::seed_from_u64(1));
+        assert_eq!(count(&grid, Cell::Mineral), 2);
+    }
+
+    #[test]
+    fn test_terrain_layer_is_seeded_and_clear_under_the_station() {
+        let mut map = Map::new(40, 30, 9);
+        assert_eq!(map.terrain, Map::new(40, 30, 9).terrain);
+        assert_eq!(map.terrain.len(), 30);
+        assert!(map.terrain.iter().all(|row| row.len() == 40));
+        assert!(map.terrain.iter().flatten().any(|terrain| *terrain != Terrain::Plain));
+
+        map.terrain[5][5] = Terrain::Rock;
+        map.place_station(5, 5);
+        assert_eq!(map.terrain_at(5, 5), Terrain::Plain);
+        assert_eq!(map.move_cost(5, 5), 1);
+    }
+
+    // Open 10x10 map with a mineral walled in at (7, 7)
+    fn walled_resource_map() -> Map {
+        let mut map = open_map(10, 10);
+        for y in 5..10 {
+            map.grid[y][5] = Cell::Obstacle;
+        }
+        for x in 5..10 {
+            map.grid[5][x] = Cell::Obstacle;
+        }
+        map.grid[7][7] = Cell::Mineral;
+        map.deposits.insert((7, 7), Deposit::new(4));
+        map
+    }
+
+    #[test]
+    fn test_connectivity_reports_walled_off_resources() {
+        let mut map = walled_resource_map();
+        assert_eq!(components(&map).len(), 2);
+
+        let report = map.ensure_connectivity((0, 0), Connectivity::Ignore);
+        assert_eq!((report.components, report.reachable, report.unreachable), (2, 0, 1));
+        assert_eq!(map.grid[7][7], Cell::Mineral);
+    }
+
+    #[test]
+    fn test_connectivity_carves_a_passage() {
+        let mut map = walled_resource_map();
+        let report = map.ensure_connectivity((0, 0), Connectivity::Carve);
+        assert_eq!((report.reachable, report.unreachable, report.carved), (1, 0, 1));
+        assert!(reachable_cells(&map, (0, 0)).contains(&(7, 7)));
+    }
+
+    #[test]
+    fn test_connectivity_treats_water_as_a_wall() {
+        let mut map = open_map(10, 10);
+        for y in 6..=8 {
+            for x in 6..=8 {
+                map.terrain[y][x] = Terrain::Water;
+            }
+        }
+        map.grid[7][7] = Cell::Mineral;
+        map.deposits.insert((7, 7), Deposit::new(4));
+
+        let report = map.clone().ensure_connectivity((0, 0), Connectivity::Ignore);
+        assert_eq!((report.reachable, report.unreachable), (0, 1));
+
+        let report = map.ensure_connectivity((0, 0), Connectivity::Carve);
+        assert_eq!((report.reachable, report.unreachable, report.carved), (1, 0, 2));
+        assert_eq!(map.terrain_at(7, 7), Terrain::Plain);
+        let mut collector = Robot::new(0, 0, Direction::East, RobotRole::Collector);
+        collector.impassable = vec![Terrain::Water];
+        collector.move_dijkstra_to(&mut map, 7, 7);
+        assert!(!collector.unreachable.contains(&(7, 7)));
+    }
+
+    #[test]
+    fn test_connectivity_relocates_resources() {
+        let mut map = walled_resource_map();
+        let report = map.ensure_connectivity((0, 0), Connectivity::Relocate);
+        assert_eq!((report.reachable, report.unreachable, report.relocated), (1, 0, 1));
+        assert_eq!(map.grid[7][7], Cell::Empty);
+        assert_eq!(map.remaining(Cell::Mineral), 4);
+        let reachable = reachable_cells(&map, (0, 0));
+        assert!(map.deposits.keys().all(|position| reachable.contains(position)));
+    }
+
+    #[test]
+    fn test_text_export_loads_back_exactly() {
+        let registry = RoleRegistry::default();
+        let mut map = Map::new(30, 20, 7);
+        let (&(x, y), _) = map.deposits.iter().next().unwrap();
+        map.terrain[y][x] = Terrain::Mud;
+        map.extract(x, y);
+        let empty = (0..30).flat_map(|x| (0..20).map(move |y| (x, y))).filter(|&(x, y)| map.grid[y][x] == Cell::Empty);
+        let empty: Vec<(usize, usize)> = empty.take(2).collect();
+        map.build(empty[0].0, empty[0].1, Structure::Beacon);
+        map.build(empty[1].0, empty[1].1, Structure::Outpost);
+
+        let loaded = MapFile::parse(&map.to_text(), &registry).unwrap();
+        assert_eq!((loaded.map.width, loaded.map.height), (30, 20));
+        assert_eq!(loaded.map.grid, map.grid);
+        assert_eq!(loaded.map.terrain, map.terrain);
+        assert_eq!(loaded.map.deposits, map.deposits);
+        assert_eq!(loaded.map.structures, map.structures);
+        assert_eq!(loaded.station, None);
+
+        // `Map::display` symbols are accepted too
+        let spaced = MapFile::parse(" O  E  P \n M  S  E \n", &registry).unwrap();
+        assert_eq!(spaced.map.grid[1], vec![Cell::Mineral, Cell::Science, Cell::Empty]);
+    }
+
+    #[test]
+    fn test_text_map_with_station_and_spawns() {
+        let registry = RoleRegistry::default();
+        let file = MapFile::parse("H e . ~\nO c M ^\n", &registry).unwrap();
+        assert_eq!(file.station, Some((0, 0)));
+        assert_eq!(file.map.terrain[0][3], Terrain::Water);
+        assert_eq!(file.map.grid[1][2], Cell::Mineral);
+        let robots = file.robots(&registry);
+        assert_eq!(robots.iter().map(|robot| (robot.x, robot.y, robot.role)).collect::<Vec<_>>(),
+            vec![(1, 0, RobotRole::Explorer), (1, 1, RobotRole::Collector)]);
+        let half = (DEPOSIT_MIN_QUANTITY + DEPOSIT_MAX_QUANTITY) / 2;
+        let expected = format!("H e . ~\nO c M ^\n---\ndeposit 2 1 {} {}\n", half, half);
+        assert_eq!(file.map.to_text_with_entities(file.station, &robots, &registry), expected);
+
+        // A robot standing on a resource is left out rather than hiding it
+        let miner = Robot::new(2, 1, Direction::East, RobotRole::Collector);
+        let text = file.map.to_text_with_entities(file.station, &[miner], &registry);
+        assert_eq!(MapFile::parse(&text, &registry).unwrap().map.grid, file.map.grid);
+        assert!(matches!(MapFile::parse("E M\n---\ndeposit 0 0 5 5\n", &registry), Err(MapFileError::InvalidEntry { line: 3 })));
+        assert!(matches!(MapFile::parse("E M\n---\ndeposit 1 0 0 5\n", &registry), Err(MapFileError::InvalidEntry { line: 3 })));
+        assert!(matches!(MapFile::parse("E M\n---\ndeposit 1 0 6 5\n", &registry), Err(MapFileError::InvalidEntry { line: 3 })));
+        assert!(matches!(MapFile::parse("E M\n---\nstructure 1 0 beacon\n", &registry), Err(MapFileError::InvalidEntry { line: 3 })));
+        assert!(matches!(MapFile::parse("E M\n---\nstructure 0 0 beacon\nstructure 0 0 outpost\n", &registry), Err(MapFileError::InvalidEntry { line: 4 })));
+
+        assert!(matches!(MapFile::parse("E E\nE\n", &registry), Err(MapFileError::RaggedRow { line: 2, expected: 2, found: 1 })));
+        assert!(matches!(MapFile::parse("E X\n", &registry), Err(MapFileError::UnknownSymbol { symbol: 'X', .. })));
+        assert!(matches!(MapFile::parse("H H\n", &registry), Err(MapFileError::SeveralStations { .. })));
+        assert!(matches!(MapFile::parse("\n", &registry), Err(MapFileError::Empty)));
+    }
+
+    #[test]
+    fn test_png_export() {
+        let map = Map::new(12, 8, 3);
+        let path = std::env::temp_dir().join("projet_essaim_test_map.png");
+        map.save_png(&path, 4, Some((0, 0))).unwrap();
+
+        let decoder = png::Decoder::new(std::fs::File::open(&path).unwrap());
+        let reader = decoder.read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (48, 32));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_try_new_validates_dimensions_and_station() {
+        assert_eq!(Map::try_new(0, 10, 42, (0, 0)).unwrap_err(), MapError::EmptyDimensions { width: 0, height: 10 });
+        assert_eq!(Map::try_new(10, 10, 42, (10, 3)).unwrap_err(), MapError::StationOutOfBounds { x: 10, y: 3, width: 10, height: 10 });
+
+        let (map, _) = Map::try_new(20, 15, 42, (4, 6)).unwrap();
+        assert_eq!((map.width, map.height), (20, 15));
+        assert_eq!(map.grid[6][4], Cell::Empty);
+        assert_eq!(map.terrain_at(4, 6), Terrain::Plain);
+    }
+
+    #[test]
+    fn test_try_generate_validates_densities() {
+        let perlin = PerlinGenerator { scale: PERLIN_SCALE, threshold: PERLIN_THRESHOLD };
+        let layout = |cell, density| ResourceLayout { cell, density, cluster_size: 1, shape: ClusterShape::Scattered };
+
+        let negative = [layout(Cell::Energy, -0.1)];
+        assert_eq!(Map::try_generate(10, 10, 1, (0, 0), &perlin, &negative, Connectivity::Ignore).unwrap_err(), MapError::InvalidDensity { cell: Cell::Energy, density: -0.1 });
+        let overflow = [layout(Cell::Energy, 0.6), layout(Cell::Mineral, 0.6)];
+        assert!(matches!(Map::try_generate(10, 10, 1, (0, 0), &perlin, &overflow, Connectivity::Ignore), Err(MapError::DensityOverflow { .. })));
+
+        // Only the station cell is open on an ocean without islands
+        let ocean = IslandsGenerator { islands: 0, radius: 0.2 };
+        let sparse = [layout(Cell::Science, 0.05)];
+        assert_eq!(Map::try_generate(10, 10, 1, (5, 5), &ocean, &sparse, Connectivity::Ignore).unwrap_err(), MapError::NoRoomForResources { wanted: 5, available: 0 });
+    }
+
+    struct OpenGenerator;
+
+    impl MapGenerator for OpenGenerator {
+        fn generate(&self, width: usize, height: usize, _seed: u64) -> Vec<Vec<Cell>> {
+            vec![vec![Cell::Empty; width]; height]
+        }
+    }
+
+    #[test]
+    fn test_resources_never_land_on_the_station() {
+        let field = |cell, density| ResourceLayout { cell, density, cluster_size: 20, shape: ClusterShape::Field };
+        let layouts = [field(Cell::Mineral, 0.5), field(Cell::Energy, 0.49)];
+        let wanted: usize = layouts.iter().map(|layout| (100.0 * layout.density) as usize).sum();
+
+        for seed in 0..10 {
+            let (map, _) = Map::try_generate(10, 10, seed, (5, 5), &OpenGenerator, &layouts, Connectivity::Ignore).unwrap();
+            let resources = map.grid.iter().flatten().filter(|cell| cell.is_resource()).count();
+            assert_eq!(resources, wanted);
+            assert_eq!(map.grid[5][5], Cell::Empty);
+        }
+    }
+
+    // Open grid with its lower right corner walled off
+    struct WalledGenerator;
+
+    impl MapGenerator for WalledGenerator {
+        fn generate(&self, width: usize, height: usize, _seed: u64) -> Vec<Vec<Cell>> {
+            let mut grid = vec![vec![Cell::Empty; width]; height];
+            for row in &mut grid[5..] {
+                row[5] = Cell::Obstacle;
+            }
+            grid[5][5..].fill(Cell::Obstacle);
+            grid
+        }
+    }
+
+    #[test]
+    fn test_try_generate_reports_the_connectivity_it_enforced() {
+        let layouts = [ResourceLayout { cell: Cell::Mineral, density: 0.3, cluster_size: 1, shape: ClusterShape::Scattered }];
+
+        let (map, report) = Map::try_generate(10, 10, 3, (0, 0), &WalledGenerator, &layouts, Connectivity::Ignore).unwrap();
+        assert_eq!(report.unreachable, map.deposits.keys().filter(|&&(x, y)| x > 5 && y > 5).count());
+        assert!(report.unreachable > 0);
+
+        let (map, report) = Map::try_generate(10, 10, 3, (0, 0), &WalledGenerator, &layouts, Connectivity::Carve).unwrap();
+        assert_eq!(report.unreachable, 0);
+        assert!(report.carved > 0);
+        let reachable = reachable_cells(&map, (0, 0));
+        assert!(map.deposits.keys().all(|position| reachable.contains(position)));
+    }
+}
@@ -1,9 +1,14 @@
-use projet_essaim::robot::{Robot, Direction, RobotRole};
-use projet_essaim::map::Map;
+use projet_essaim::robot::{Robot, RobotId, Direction, RobotRole, RobotBehavior, RoleDefinition, RoleRegistry, RobotState, Inventory};
+use projet_essaim::station::Station;
+use projet_essaim::map::{Map, Cell, Deposit, Structure, Terrain};
+use projet_essaim::config::{ACTION_POINTS, MUD_MOVE_COST, BATTERY_CAPACITY, MOVE_ENERGY_COST, MINERAL_WEIGHT, ENERGY_WEIGHT, BEACON_RECIPE, BEACON_SPACING, RELAY_SPACING};
+
+mod common;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::{custom_role, open_map};
 
     #[test]
     fn test_robot_creation() {
@@ -31,4 +36,298 @@ mod tests {
         assert_eq!(robot.x, 1);
         assert_eq!(robot.y, 0);
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_robot_battery_drains_on_move() {
+        let mut robot = Robot::new(0, 0, Direction::East, RobotRole::Explorer);
+        let map = Map::new(10, 10, 42);
+        robot.move_forward(&map);
+        assert_eq!(robot.battery, BATTERY_CAPACITY - MOVE_ENERGY_COST);
+    }
+
+    #[test]
+    fn test_robot_wreck_stays_in_place() {
+        let mut robot = Robot::new(0, 0, Direction::East, RobotRole::Explorer);
+        let map = Map::new(10, 10, 42);
+        robot.battery = 0;
+        robot.move_forward(&map);
+        assert!(robot.is_wreck());
+        assert_eq!((robot.x, robot.y), (0, 0));
+    }
+
+    struct SpinBehavior;
+
+    impl RobotBehavior for SpinBehavior {
+        fn act(&self, robot: &mut Robot, _role: &RoleDefinition, _map: &mut Map, _station_x: usize, _station_y: usize, _station: &mut Station) {
+            robot.turn_left();
+        }
+    }
+
+    #[test]
+    fn test_custom_role_behavior() {
+        let spinner = RobotRole::Custom("spinner");
+        let mut registry = RoleRegistry::default();
+        registry.register(spinner, custom_role("Spinner", 'x', SpinBehavior));
+
+        let mut map = Map::new(10, 10, 42);
+        let mut station = Station::new(0, 0);
+        let mut robot = Robot::new(0, 0, Direction::North, spinner);
+        robot.act(&mut map, 0, 0, &mut station, &registry);
+        assert_eq!(robot.direction, Direction::West);
+    }
+
+    #[test]
+    fn test_robot_state_transitions_are_recorded() {
+        let mut robot = Robot::new(0, 0, Direction::North, RobotRole::Collector);
+        assert_eq!(robot.state, RobotState::Idle);
+
+        robot.set_state(RobotState::Searching, "no resource known");
+        robot.set_state(RobotState::Searching, "still nothing");
+        robot.set_state(RobotState::Collecting, "standing on a resource");
+
+        let transitions = robot.take_transitions();
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[0].from, RobotState::Idle);
+        assert_eq!(transitions[1].to, RobotState::Collecting);
+        assert!(robot.take_transitions().is_empty());
+    }
+
+    #[test]
+    fn test_empty_battery_turns_robot_into_wreck() {
+        let registry = RoleRegistry::default();
+        let mut map = Map::new(10, 10, 42);
+        let mut station = Station::new(9, 9);
+        let mut robot = Robot::new(0, 0, Direction::East, RobotRole::Explorer);
+        robot.battery = 0;
+
+        robot.act(&mut map, 9, 9, &mut station, &registry);
+        assert_eq!(robot.state, RobotState::Wreck);
+        assert_eq!((robot.x, robot.y), (0, 0));
+    }
+
+    #[test]
+    fn test_inventory_weights_and_free_capacity() {
+        let mut inventory = Inventory::new(MINERAL_WEIGHT + ENERGY_WEIGHT);
+        assert!(inventory.add(Cell::Mineral));
+        assert_eq!(inventory.free_capacity(), ENERGY_WEIGHT);
+        assert!(!inventory.can_carry(Cell::Mineral));
+        assert!(inventory.add(Cell::Energy));
+        assert!(inventory.is_full());
+        assert!(!inventory.add(Cell::Energy));
+        assert_eq!(inventory.drain(), vec![Cell::Mineral, Cell::Energy]);
+        assert!(inventory.is_empty());
+    }
+
+    // Station that has seen the whole map, with every resource posted on its task board
+    fn station_knowing(map: &Map) -> Station {
+        let mut station = Station::new(9, 9);
+        for (y, row) in map.grid.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                station.discovered.insert((x, y), cell);
+            }
+        }
+        station.tasks.refresh(1, map, &station.discovered);
+        station
+    }
+
+    #[test]
+    fn test_collector_only_targets_resources_the_station_knows() {
+        let mut map = open_map(10, 10);
+        map.grid[0][1] = Cell::Energy;
+        map.grid[0][5] = Cell::Mineral;
+
+        let mut station = Station::new(9, 9);
+        station.discovered.insert((5, 0), Cell::Mineral);
+        station.tasks.refresh(1, &map, &station.discovered);
+        let mut robot = Robot::new(0, 0, Direction::North, RobotRole::Collector);
+        robot.inventory.set_capacity(4);
+        assert_eq!(robot.find_nearest_unclaimed_resource(&map, &station), Some((5, 0)));
+    }
+
+    #[test]
+    fn test_collector_follows_target_resource() {
+        let mut map = open_map(10, 10);
+        map.grid[0][1] = Cell::Energy;
+        map.grid[2][0] = Cell::Mineral;
+
+        let station = station_knowing(&map);
+        let mut robot = Robot::new(0, 0, Direction::North, RobotRole::Collector);
+        robot.inventory.set_capacity(4);
+        assert_eq!(robot.find_nearest_unclaimed_resource(&map, &station), Some((1, 0)));
+
+        robot.set_target_resource(Some(Cell::Mineral));
+        assert_eq!(robot.find_nearest_unclaimed_resource(&map, &station), Some((0, 2)));
+    }
+
+    #[test]
+    fn test_collectors_share_a_deposit_over_several_ticks() {
+        let mut map = open_map(10, 10);
+        map.grid[3][3] = Cell::Mineral;
+        map.deposits.insert((3, 3), Deposit::new(3));
+
+        let mut first = Robot::new(3, 3, Direction::North, RobotRole::Collector);
+        let mut second = Robot::new(3, 3, Direction::North, RobotRole::Collector);
+        first.inventory.set_capacity(4);
+        second.inventory.set_capacity(4);
+
+        assert_eq!(first.mine(&mut map), 1);
+        assert_eq!(second.mine(&mut map), 1);
+        assert_eq!(map.quantity(3, 3), 1);
+
+        assert_eq!(first.mine(&mut map), 1);
+        assert_eq!(second.mine(&mut map), 0);
+        assert_eq!(map.grid[3][3], Cell::Empty);
+        assert_eq!(first.inventory.count(Cell::Mineral), 2);
+        assert!(map.deposits.is_empty());
+    }
+
+    #[test]
+    fn test_scientist_analysis_reveals_deposits() {
+        let mut map = open_map(20, 20);
+        map.grid[10][10] = Cell::Science;
+        map.deposits.insert((10, 10), Deposit::new(5));
+        map.grid[12][13] = Cell::Mineral;
+        map.deposits.insert((13, 12), Deposit::new(7));
+        map.grid[0][0] = Cell::Energy;
+
+        let registry = RoleRegistry::default();
+        let mut station = Station::new(0, 19);
+        let mut robot = Robot::new(10, 10, Direction::North, RobotRole::Scientist);
+        robot.act(&mut map, 0, 19, &mut station, &registry);
+
+        assert_eq!(robot.state, RobotState::Analysing);
+        assert!(robot.is_busy());
+        assert_eq!(map.quantity(10, 10), 4);
+        let result = &robot.research_results[0];
+        assert!(result.deposits.contains(&((13, 12), Cell::Mineral, 7)));
+        assert!(!result.deposits.iter().any(|&(position, _, _)| position == (0, 0)));
+
+        robot.report_research(&mut station);
+        assert!(station.is_analysed(10, 10));
+        assert_eq!(station.discovered.get(&(13, 12)), Some(&Cell::Mineral));
+        assert_eq!(station.known_deposits[&(13, 12)], (Cell::Mineral, 7));
+        assert_eq!(robot.find_nearest_scientist_position(&map, &station), None);
+    }
+
+    #[test]
+    fn test_builder_fetches_materials_and_builds_away_from_station() {
+        let mut map = open_map(20, 20);
+        let registry = RoleRegistry::default();
+        let mut station = Station::new(0, 0);
+        for &(cell, amount) in BEACON_RECIPE {
+            station.resources_collected.insert(cell, amount);
+        }
+
+        let mut builder = Robot::new(0, 0, Direction::North, RobotRole::Builder);
+        builder.act(&mut map, 0, 0, &mut station, &registry);
+        for &(cell, amount) in BEACON_RECIPE {
+            assert_eq!(builder.inventory.count(cell), amount);
+            assert_eq!(station.resources_collected[&cell], 0);
+        }
+
+        let (site_x, site_y) = builder.find_build_site(&map, 0, 0).unwrap();
+        assert!(site_x + site_y >= BEACON_SPACING);
+
+        (builder.x, builder.y) = (site_x, site_y);
+        builder.act(&mut map, 0, 0, &mut station, &registry);
+        assert_eq!(map.structures.get(&(site_x, site_y)), Some(&Structure::Beacon));
+        assert!(builder.inventory.is_empty());
+        assert_eq!(builder.state, RobotState::Building { x: site_x, y: site_y });
+    }
+
+    #[test]
+    fn test_relay_parks_far_from_station() {
+        let mut map = open_map(20, 20);
+        let registry = RoleRegistry::default();
+        let mut station = Station::new(0, 0);
+
+        let mut relay = Robot::new(0, RELAY_SPACING, Direction::North, RobotRole::Relay);
+        relay.act(&mut map, 0, 0, &mut station, &registry);
+        assert_eq!(relay.state, RobotState::Parked { x: relay.x, y: relay.y });
+        assert_eq!((relay.x, relay.y), (0, RELAY_SPACING));
+    }
+
+    #[test]
+    fn test_collectors_claim_distinct_deposits() {
+        let mut map = open_map(10, 10);
+        map.grid[0][3] = Cell::Energy;
+        map.grid[3][0] = Cell::Energy;
+        let registry = RoleRegistry::default();
+        let mut station = station_knowing(&map);
+
+        let mut first = Robot::new(0, 0, Direction::North, RobotRole::Collector);
+        let mut second = Robot::new(0, 0, Direction::North, RobotRole::Collector);
+        first.id = RobotId(1);
+        second.id = RobotId(2);
+        first.act(&mut map, 9, 9, &mut station, &registry);
+        second.act(&mut map, 9, 9, &mut station, &registry);
+
+        let first_target = station.tasks.claimed_by(first.id).unwrap();
+        let second_target = station.tasks.claimed_by(second.id).unwrap();
+        assert_ne!(first_target, second_target);
+        assert!(matches!(second.state, RobotState::TravellingToResource { x, y } if (x, y) == second_target));
+    }
+
+    #[test]
+    fn test_dijkstra_goes_around_slow_terrain() {
+        let mut map = open_map(10, 10);
+        for x in 1..=3 {
+            map.terrain[0][x] = Terrain::Mud;
+        }
+
+        let mut robot = Robot::new(0, 0, Direction::East, RobotRole::Explorer);
+        robot.move_dijkstra_to(&mut map, 4, 0);
+        assert_eq!(robot.path_costs(&map)[&(4, 0)], 6);
+        assert_eq!(robot.current_path.last(), Some(&(4, 0)));
+        assert!((1..=3).all(|x| !robot.current_path.contains(&(x, 0))));
+    }
+
+    #[test]
+    fn test_nearest_resource_is_the_cheapest_to_reach() {
+        let mut map = open_map(10, 10);
+        for y in 1..=2 {
+            for x in 0..10 {
+                map.terrain[y][x] = Terrain::Mud;
+            }
+        }
+        map.grid[2][0] = Cell::Mineral;
+        map.grid[0][4] = Cell::Mineral;
+
+        let station = station_knowing(&map);
+        let mut robot = Robot::new(0, 0, Direction::East, RobotRole::Collector);
+        robot.inventory.set_capacity(4);
+        assert_eq!(robot.find_nearest_unclaimed_resource(&map, &station), Some((4, 0)));
+    }
+
+    #[test]
+    fn test_terrain_slows_and_blocks_robots() {
+        let mut map = open_map(10, 10);
+        map.terrain[0][1] = Terrain::Mud;
+        map.terrain[1][0] = Terrain::Water;
+
+        let mut robot = Robot::new(0, 0, Direction::South, RobotRole::Collector);
+        robot.impassable = vec![Terrain::Water];
+        robot.move_forward(&map);
+        assert_eq!((robot.x, robot.y), (0, 0));
+
+        robot.direction = Direction::East;
+        robot.move_forward(&map);
+        assert_eq!((robot.x, robot.y), (1, 0));
+        assert_eq!(robot.move_debt, (MUD_MOVE_COST - 1) * ACTION_POINTS);
+    }
+
+    #[test]
+    fn test_robot_gives_up_on_unreachable_target() {
+        let mut map = open_map(10, 10);
+        for x in 0..10 {
+            map.grid[5][x] = Cell::Obstacle;
+        }
+
+        let mut robot = Robot::new(2, 2, Direction::South, RobotRole::Explorer);
+        for _ in 0..3 {
+            robot.move_dijkstra_to(&mut map, 2, 8);
+        }
+        assert_eq!((robot.x, robot.y), (2, 2));
+        assert!(robot.unreachable.contains(&(2, 8)));
+    }
+}
@@ -0,0 +1,414 @@
+use projet_essaim::simulation::{Simulation, SimulationEvent};
+use projet_essaim::robot::{Robot, RobotId, Direction, RobotRole, RoleRegistry, RoleDefinition, RobotBehavior, RobotState, Action};
+use projet_essaim::config::{UPKEEP_CREDIT, STATION_RECHARGE_RATE, OUTPOST_RECIPE, ACTION_POINTS, LOAD_SPEED_PENALTY, COLLECTOR_SPEED, PICKUP_DURATION};
+use projet_essaim::station::Station;
+use projet_essaim::station::tasks::Allocation;
+use projet_essaim::simulation::mission::{Mission, Objective, Outcome};
+use projet_essaim::map::{Map, Cell, Structure};
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{custom_role, open_map};
+
+    fn new_simulation() -> Simulation {
+        Simulation::new(Map::new(10, 10, 42), Station::new(5, 5), RoleRegistry::default())
+    }
+
+    #[test]
+    fn test_simulation_leaves_the_map_as_given() {
+        let mut map = open_map(10, 10);
+        for i in 5..10 {
+            map.grid[i][5] = Cell::Obstacle;
+            map.grid[5][i] = Cell::Obstacle;
+        }
+        map.grid[7][7] = Cell::Mineral;
+        let grid = map.grid.clone();
+
+        let simulation = Simulation::new(map, Station::new(0, 0), RoleRegistry::default());
+        assert_eq!(simulation.map.grid, grid);
+    }
+
+    #[test]
+    fn test_production_sees_walled_off_cells_as_explored() {
+        let mut map = open_map(10, 10);
+        for i in 5..10 {
+            map.grid[i][5] = Cell::Obstacle;
+            map.grid[5][i] = Cell::Obstacle;
+        }
+        let mut simulation = Simulation::new(map, Station::new(0, 0), RoleRegistry::default());
+        for y in 0..10 {
+            for x in 0..10 {
+                if (x < 5 || y < 5) && simulation.map.grid[y][x] != Cell::Obstacle {
+                    simulation.station.discovered.insert((x, y), Cell::Empty);
+                }
+            }
+        }
+        assert_eq!(simulation.production_context().explored, 1.0);
+    }
+
+    #[test]
+    fn test_robot_ids_are_stable_after_removal() {
+        let mut simulation = new_simulation();
+        let first = simulation.add_robot(Robot::new(5, 5, Direction::North, RobotRole::Explorer));
+        let second = simulation.add_robot(Robot::new(5, 5, Direction::North, RobotRole::Collector).with_name("Digger"));
+        assert_ne!(first, second);
+
+        simulation.remove_robot(first);
+        let third = simulation.add_robot(Robot::new(5, 5, Direction::North, RobotRole::Scientist));
+        assert_ne!(third, first);
+        assert_ne!(third, second);
+
+        let robot = simulation.robot(second).unwrap();
+        assert_eq!(robot.role, RobotRole::Collector);
+        assert_eq!(robot.label(), "Digger #2");
+        assert!(simulation.robot(first).is_none());
+    }
+
+    #[test]
+    fn test_step_reports_events_by_robot_id() {
+        let mut simulation = new_simulation();
+        let id = simulation.add_robot(Robot::new(5, 5, Direction::North, RobotRole::Explorer));
+
+        let events = simulation.step();
+        assert_eq!(simulation.tick, 1);
+        assert!(events.iter().any(|event| matches!(event,
+            SimulationEvent::StateChanged { tick: 1, robot, .. } if *robot == id)));
+        assert_eq!(simulation.events, events);
+    }
+
+    #[test]
+    fn test_unnamed_robot_label_uses_role() {
+        let mut robot = Robot::new(0, 0, Direction::North, RobotRole::Scientist);
+        robot.id = RobotId(7);
+        assert_eq!(robot.label(), "Scientist #7");
+    }
+
+    #[test]
+    fn test_failures_stop_robots() {
+        let mut simulation = new_simulation();
+        simulation.failure_probability = 1.0;
+        let id = simulation.add_robot(Robot::new(5, 5, Direction::North, RobotRole::Explorer));
+
+        simulation.step();
+        let position = (simulation.robot(id).unwrap().x, simulation.robot(id).unwrap().y);
+        assert!(simulation.robot(id).unwrap().is_broken());
+
+        simulation.step();
+        let robot = simulation.robot(id).unwrap();
+        assert_eq!((robot.x, robot.y), position);
+        assert_eq!(robot.state, RobotState::Broken);
+    }
+
+    struct IdleBehavior;
+
+    impl RobotBehavior for IdleBehavior {
+        fn act(&self, _robot: &mut Robot, _role: &RoleDefinition, _map: &mut Map, _station_x: usize, _station_y: usize, _station: &mut Station) {}
+    }
+
+    #[test]
+    fn test_broken_robot_is_towed_home_and_repaired() {
+        let tug = RobotRole::Custom("tug");
+        let mut registry = RoleRegistry::default();
+        registry.register(tug, custom_role("Tug", 't', IdleBehavior));
+        let mut simulation = Simulation::new(open_map(10, 10), Station::new(5, 5), registry);
+        simulation.failure_probability = 0.0;
+        simulation.station.resources_collected.insert(Cell::Mineral, 5);
+
+        let broken = simulation.add_robot(Robot::new(5, 7, Direction::North, RobotRole::Collector));
+        let tower = simulation.add_robot(Robot::new(5, 8, Direction::North, tug));
+        simulation.robot_mut(broken).unwrap().break_down("test");
+
+        simulation.step();
+        assert_eq!(simulation.robot(broken).unwrap().towed_by, Some(tower));
+        assert_eq!(simulation.robot(tower).unwrap().towing, Some(broken));
+
+        for _ in 0..4 { // One tick to plan the route, then three moves
+            simulation.step();
+        }
+        let robot = simulation.robot(broken).unwrap();
+        assert_eq!((robot.x, robot.y), (5, 5));
+        assert!(!robot.is_broken());
+        assert_eq!(simulation.robot(tower).unwrap().towing, None);
+        assert_eq!(simulation.station.resources_collected[&Cell::Mineral], 3);
+    }
+
+    #[test]
+    fn test_loaded_collector_is_slower() {
+        let registry = RoleRegistry::default();
+        let definition = registry.get(RobotRole::Collector).unwrap();
+        let explorer = Robot::new(0, 0, Direction::North, RobotRole::Explorer);
+        let mut collector = Robot::new(0, 0, Direction::North, RobotRole::Collector);
+        collector.inventory.set_capacity(4);
+        assert_eq!(collector.speed(definition), COLLECTOR_SPEED);
+        assert!(explorer.speed(registry.get(RobotRole::Explorer).unwrap()) >= 2 * ACTION_POINTS);
+
+        collector.inventory.add(Cell::Mineral);
+        assert_eq!(collector.speed(definition), COLLECTOR_SPEED - 2 * LOAD_SPEED_PENALTY);
+    }
+
+    #[test]
+    fn test_busy_robot_skips_ticks() {
+        let mut simulation = new_simulation();
+        simulation.failure_probability = 0.0;
+        let id = simulation.add_robot(Robot::new(5, 5, Direction::North, RobotRole::Explorer));
+        simulation.robot_mut(id).unwrap().begin_action(Action::Pickup);
+        let battery = simulation.robot(id).unwrap().battery;
+
+        for _ in 1..PICKUP_DURATION {
+            assert!(simulation.robot(id).unwrap().is_busy());
+            simulation.step();
+        }
+        let robot = simulation.robot(id).unwrap();
+        assert!(!robot.is_busy());
+        assert_eq!(robot.battery, battery);
+    }
+
+    #[test]
+    fn test_hauler_takes_cargo_from_collector() {
+        let mut simulation = new_simulation();
+        simulation.failure_probability = 0.0;
+        simulation.map.grid[2][2] = Cell::Empty;
+        let mut collector = Robot::new(2, 2, Direction::North, RobotRole::Collector);
+        collector.break_down("test");
+        collector.inventory.set_capacity(4);
+        collector.inventory.add(Cell::Mineral);
+        let collector = simulation.add_robot(collector);
+        let hauler = simulation.add_robot(Robot::new(2, 2, Direction::North, RobotRole::Hauler));
+
+        simulation.step();
+        assert!(simulation.robot(collector).unwrap().inventory.is_empty());
+        assert_eq!(simulation.robot(hauler).unwrap().inventory.count(Cell::Mineral), 1);
+    }
+
+    #[test]
+    fn test_hauler_reports_meeting_a_collector() {
+        let mut simulation = Simulation::new(open_map(10, 10), Station::new(5, 5), RoleRegistry::default());
+        simulation.failure_probability = 0.0;
+        let mut collector = Robot::new(8, 5, Direction::North, RobotRole::Collector);
+        collector.break_down("test");
+        collector.inventory.set_capacity(4);
+        collector.inventory.add(Cell::Mineral);
+        simulation.add_robot(collector);
+        let hauler = simulation.add_robot(Robot::new(5, 5, Direction::North, RobotRole::Hauler));
+
+        simulation.step();
+        assert_eq!(simulation.robot(hauler).unwrap().state, RobotState::MeetingCollector { x: 8, y: 5 });
+        assert!(simulation.events.iter().any(|event| matches!(event,
+            SimulationEvent::StateChanged { robot, transition, .. } if *robot == hauler && transition.to == RobotState::MeetingCollector { x: 8, y: 5 })));
+    }
+
+    #[test]
+    fn test_hauler_drops_its_route_home_to_meet_a_collector() {
+        let mut simulation = Simulation::new(open_map(10, 10), Station::new(5, 5), RoleRegistry::default());
+        simulation.failure_probability = 0.0;
+        let mut collector = Robot::new(8, 8, Direction::North, RobotRole::Collector);
+        collector.break_down("test");
+        collector.inventory.set_capacity(4);
+        collector.inventory.add(Cell::Mineral);
+        simulation.add_robot(collector);
+
+        let mut hauler = Robot::new(0, 0, Direction::North, RobotRole::Hauler);
+        hauler.set_state(RobotState::ReturningToStation, "test");
+        hauler.move_dijkstra_to(&mut simulation.map, 5, 5);
+        assert_eq!(hauler.path_target, Some((5, 5)));
+        let hauler = simulation.add_robot(hauler);
+
+        simulation.step();
+        let hauler = simulation.robot(hauler).unwrap();
+        assert_eq!(hauler.state, RobotState::MeetingCollector { x: 8, y: 8 });
+        assert_eq!(hauler.path_target, Some((8, 8)));
+    }
+
+    #[test]
+    fn test_docked_robot_recharges_once_per_tick() {
+        let mut simulation = new_simulation();
+        simulation.failure_probability = 0.0;
+        let (x, y) = (simulation.station.x, simulation.station.y);
+        let mut explorer = Robot::new(x, y, Direction::North, RobotRole::Explorer);
+        explorer.battery = 10;
+        explorer.set_state(RobotState::Recharging, "test");
+        explorer.speed_points = 2 * ACTION_POINTS; // Enough for several actions this tick
+        let id = simulation.add_robot(explorer);
+
+        simulation.step();
+        assert_eq!(simulation.robot(id).unwrap().battery, 10 + STATION_RECHARGE_RATE);
+    }
+
+    #[test]
+    fn test_beacons_reveal_their_surroundings() {
+        let mut simulation = new_simulation();
+        simulation.map.grid[0][0] = Cell::Empty;
+        assert!(simulation.map.build(0, 0, Structure::Beacon));
+        assert!(!simulation.map.build(0, 0, Structure::Beacon));
+
+        simulation.step();
+        assert!(simulation.station.discovered.contains_key(&(2, 2)));
+    }
+
+    fn simulation_with_outpost(shared_inventory: bool) -> (Simulation, RobotId) {
+        let mut simulation = new_simulation();
+        simulation.failure_probability = 0.0;
+        simulation.shared_inventory = shared_inventory;
+        simulation.map.grid[1][1] = Cell::Empty;
+        assert!(simulation.add_outpost(1, 1));
+        assert!(!simulation.add_outpost(1, 1));
+
+        let mut robot = Robot::new(1, 1, Direction::North, RobotRole::Collector);
+        robot.inventory.set_capacity(4);
+        robot.inventory.add(Cell::Energy);
+        robot.set_state(RobotState::ReturningToRecharge, "test");
+        robot.speed_points = ACTION_POINTS; // Loaded collectors are slower, let it act on the first tick
+        let id = simulation.add_robot(robot);
+        (simulation, id)
+    }
+
+    #[test]
+    fn test_robot_delivers_to_the_nearest_station() {
+        let (mut simulation, id) = simulation_with_outpost(true);
+        simulation.step();
+
+        assert!(simulation.robot(id).unwrap().inventory.is_empty());
+        assert_eq!(simulation.station.resources_collected.get(&Cell::Energy), Some(&1));
+        assert!(simulation.outposts[0].resources_collected.is_empty());
+
+        let (mut simulation, _) = simulation_with_outpost(false);
+        simulation.step();
+        assert_eq!(simulation.outposts[0].resources_collected.get(&Cell::Energy), Some(&1));
+        assert_eq!(simulation.station.resources_collected.get(&Cell::Energy), None);
+    }
+
+    #[test]
+    fn test_station_discoveries_are_merged() {
+        let (mut simulation, _) = simulation_with_outpost(true);
+        simulation.outposts[0].discovered.insert((0, 9), Cell::Empty);
+        simulation.station.discovered.insert((9, 0), Cell::Empty);
+
+        simulation.step();
+        assert!(simulation.station.discovered.contains_key(&(0, 9)));
+        assert!(simulation.outposts[0].discovered.contains_key(&(9, 0)));
+    }
+
+    #[test]
+    fn test_headquarters_founds_outpost_near_far_resources() {
+        let mut simulation = Simulation::new(Map::new(40, 40, 42), Station::new(1, 1), RoleRegistry::default());
+        simulation.failure_probability = 0.0;
+        simulation.map.grid[30][30] = Cell::Mineral;
+        simulation.map.grid[30][31] = Cell::Empty;
+        simulation.station.discovered.insert((30, 30), Cell::Mineral);
+        for &(cell, amount) in OUTPOST_RECIPE {
+            simulation.station.resources_collected.insert(cell, amount);
+        }
+
+        let events = simulation.step();
+        assert_eq!(simulation.outposts.len(), 1);
+        assert!(events.iter().any(|event| matches!(event, SimulationEvent::OutpostFounded { .. })));
+        assert!(OUTPOST_RECIPE.iter().all(|(cell, _)| simulation.station.resources_collected[cell] == 0));
+        let outpost = &simulation.outposts[0];
+        assert_eq!(simulation.map.structures.get(&(outpost.x, outpost.y)), Some(&Structure::Outpost));
+    }
+
+    #[test]
+    fn test_auction_sends_collector_to_the_task_it_won() {
+        let mut simulation = Simulation::new(open_map(10, 10), Station::new(5, 5), RoleRegistry::default())
+            .with_allocation(Allocation::Auction);
+        simulation.failure_probability = 0.0;
+        simulation.map.grid[5][8] = Cell::Energy;
+        simulation.map.grid[1][1] = Cell::Energy; // On the map but unknown to the station
+        simulation.station.discovered.insert((8, 5), Cell::Energy);
+
+        let mut collector = Robot::new(5, 5, Direction::North, RobotRole::Collector);
+        collector.inventory.set_capacity(4);
+        let id = simulation.add_robot(collector);
+
+        simulation.step();
+        assert_eq!(simulation.station.tasks.claimed_by(id), Some((8, 5)));
+        assert_eq!(simulation.robot(id).unwrap().state, RobotState::TravellingToResource { x: 8, y: 5 });
+    }
+
+    #[test]
+    fn test_unpaid_upkeep_shuts_down_the_newest_robot() {
+        let mut simulation = new_simulation();
+        simulation.failure_probability = 0.0;
+        let first = simulation.add_robot(Robot::new(5, 5, Direction::North, RobotRole::Explorer));
+        let newest = simulation.add_robot(Robot::new(5, 5, Direction::North, RobotRole::Explorer));
+        simulation.station.upkeep_debt = UPKEEP_CREDIT;
+
+        simulation.step();
+        assert!(simulation.robot(newest).unwrap().is_shut_down());
+        assert!(!simulation.robot(first).unwrap().is_shut_down());
+    }
+
+    #[test]
+    fn test_shut_down_robot_waits_for_the_debt_to_be_paid() {
+        let mut simulation = new_simulation();
+        simulation.failure_probability = 0.0;
+        let (x, y) = (simulation.station.x, simulation.station.y);
+        for _ in 0..2 {
+            simulation.add_robot(Robot::new(5, 5, Direction::North, RobotRole::Explorer));
+        }
+        let id = simulation.add_robot(Robot::new(x, y, Direction::North, RobotRole::Explorer));
+        simulation.station.resources_collected.insert(Cell::Mineral, 100);
+        simulation.station.upkeep_debt = UPKEEP_CREDIT;
+
+        simulation.step();
+        let robot = simulation.robot(id).unwrap();
+        let position = (robot.x, robot.y);
+        simulation.step();
+        let robot = simulation.robot(id).unwrap();
+        assert!(robot.is_shut_down());
+        assert_eq!((robot.x, robot.y), position);
+        assert_eq!(simulation.station.resources_collected[&Cell::Mineral], 100);
+
+        simulation.station.resources_collected.insert(Cell::Energy, UPKEEP_CREDIT as usize + 2);
+        simulation.step();
+        assert_eq!(simulation.robot(id).unwrap().state, RobotState::Idle);
+    }
+
+    fn mission(objectives: Vec<Objective>, time_limit: Option<u64>) -> Mission {
+        Mission { objectives, time_limit }
+    }
+
+    #[test]
+    fn test_mission_succeeds_once_objectives_are_met() {
+        let mut simulation = new_simulation().with_mission(mission(vec![Objective::Survive(2), Objective::Deliver(Cell::Mineral, 1)], None));
+        simulation.failure_probability = 0.0;
+        simulation.add_robot(Robot::new(5, 5, Direction::North, RobotRole::Explorer));
+
+        simulation.step();
+        simulation.step();
+        assert!(!simulation.is_finished());
+
+        simulation.station.receive_resources(vec![Cell::Mineral]);
+        let events = simulation.step();
+        let result = simulation.result.clone().unwrap();
+        assert_eq!((result.outcome, result.tick), (Outcome::Success, 3));
+        assert!(events.contains(&SimulationEvent::MissionEnded { tick: 3, outcome: Outcome::Success }));
+
+        assert!(simulation.step().is_empty());
+        assert_eq!(simulation.tick, 3);
+    }
+
+    #[test]
+    fn test_mission_fails_when_every_robot_is_down() {
+        let mut simulation = new_simulation().with_mission(mission(vec![Objective::Survive(100)], None));
+        simulation.failure_probability = 1.0;
+        simulation.add_robot(Robot::new(5, 5, Direction::North, RobotRole::Explorer));
+
+        simulation.step();
+        let result = simulation.result.clone().unwrap();
+        assert_eq!((result.outcome, result.tick), (Outcome::Failure, 1));
+    }
+
+    #[test]
+    fn test_mission_fails_at_time_limit() {
+        let mut simulation = new_simulation().with_mission(mission(vec![Objective::Explore(1.0)], Some(2)));
+        simulation.failure_probability = 0.0;
+        simulation.step();
+        assert!(!simulation.is_finished());
+        simulation.step();
+        assert_eq!(simulation.result.as_ref().map(|result| result.outcome), Some(Outcome::Failure));
+    }
+}
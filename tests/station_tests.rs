@@ -1,10 +1,18 @@
 use projet_essaim::station::Station;
+use projet_essaim::station::tasks::{TaskBoard, Bid};
+use projet_essaim::station::policy::{ProductionPolicy, ProductionContext, FixedRatioPolicy, DemandDrivenPolicy, ManualQueuePolicy};
+use projet_essaim::config::RESOURCES_PER_COLLECTOR;
+use std::collections::HashMap;
 use projet_essaim::map::Cell;
-use projet_essaim::robot::{Robot, RobotRole, Direction};
+use projet_essaim::robot::{Robot, RobotId, RobotRole, Direction, RoleRegistry, RoleDefinition, CollectorBehavior, Upgrade};
+use projet_essaim::config::{UPKEEP_ENERGY_PER_ROBOT, UPKEEP_CREDIT, CLAIM_DURATION, PRODUCTION_QUEUE_LENGTH, EXPLORER_RECIPE, UPGRADE_RECIPE, EXPLORER_BUILD_TIME, STATION_RECHARGE_RATE, ENERGY_UNIT_CHARGE, REPAIR_MINERAL_COST, RESEARCH_POINTS_PER_SCIENCE, UPGRADE_BASE_COST, UPGRADE_MAX_LEVEL, BATTERY_CAPACITY, BATTERY_PER_LEVEL};
+
+mod common;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::{custom_role, open_map};
 
     #[test]
     fn test_station_creation() {
@@ -37,4 +45,349 @@ mod tests {
         station.update_explorer_positions(&explorers);
         assert_eq!(station.explorer_positions, vec![(1, 1)]);
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_station_recharge_consumes_energy() {
+        let mut station = Station::new(0, 0);
+        station.receive_resources(vec![Cell::Energy, Cell::Energy]);
+        let mut robot = Robot::new(0, 0, Direction::North, RobotRole::Collector);
+        robot.battery = 10;
+
+        station.recharge_robot(&mut robot);
+        assert_eq!(robot.battery, 10 + STATION_RECHARGE_RATE + ENERGY_UNIT_CHARGE);
+        assert_eq!(station.resources_collected.get(&Cell::Energy), Some(&1));
+    }
+
+    #[test]
+    fn test_station_creates_registered_role() {
+        let mut registry = RoleRegistry::new();
+        registry.register(RobotRole::Custom("miner"), RoleDefinition {
+            cost: vec![(Cell::Mineral, 2), (Cell::Energy, 1)],
+            capacity: 3,
+            ..custom_role("Miner", 'm', CollectorBehavior)
+        });
+
+        let mut station = Station::new(0, 0);
+        station.receive_resources(vec![Cell::Mineral, Cell::Mineral, Cell::Energy]);
+        assert!(station.enqueue(RobotRole::Custom("miner"), &registry));
+        assert_eq!(station.resources_collected.get(&Cell::Mineral), Some(&0));
+        assert!(!station.enqueue(RobotRole::Custom("miner"), &registry));
+
+        let robot = station.advance_production().unwrap();
+        assert_eq!(robot.role, RobotRole::Custom("miner"));
+        assert_eq!(station.robots_created, 1);
+    }
+
+    #[test]
+    fn test_station_repair_needs_minerals() {
+        let mut station = Station::new(0, 0);
+        let mut robot = Robot::new(0, 0, Direction::North, RobotRole::Explorer);
+        robot.break_down("test");
+
+        assert!(!station.repair_robot(&mut robot));
+        assert!(robot.is_broken());
+
+        station.resources_collected.insert(Cell::Mineral, REPAIR_MINERAL_COST);
+        assert!(station.repair_robot(&mut robot));
+        assert!(!robot.is_broken());
+        assert_eq!(station.resources_collected[&Cell::Mineral], 0);
+    }
+
+    fn stock(station: &mut Station, recipe: &[(Cell, usize)], times: usize) {
+        for &(cell, amount) in recipe {
+            *station.resources_collected.entry(cell).or_insert(0) += amount * times;
+        }
+    }
+
+    #[test]
+    fn test_science_deposits_fund_research() {
+        let mut station = Station::new(0, 0);
+        station.receive_resources(vec![Cell::Science, Cell::Science, Cell::Mineral]);
+        assert_eq!(station.scientific_discoveries, 2);
+        assert_eq!(station.research_points, 2 * RESEARCH_POINTS_PER_SCIENCE);
+
+        station.research_points = UPGRADE_BASE_COST;
+        stock(&mut station, UPGRADE_RECIPE, 1);
+        assert_eq!(station.maybe_research_upgrade(), Some(Upgrade::SensorRange));
+        assert_eq!(station.research_points, 0);
+        assert_eq!(station.upgrade_cost(Upgrade::SensorRange), Some(2 * UPGRADE_BASE_COST));
+        assert!(!station.research_upgrade(Upgrade::Battery));
+    }
+
+    #[test]
+    fn test_upgrades_stop_at_max_level() {
+        let mut station = Station::new(0, 0);
+        station.research_points = 1000;
+        stock(&mut station, UPGRADE_RECIPE, 1000);
+        for _ in 0..UPGRADE_MAX_LEVEL {
+            assert!(station.research_upgrade(Upgrade::Speed));
+        }
+        assert_eq!(station.upgrade_cost(Upgrade::Speed), None);
+        assert!(!station.research_upgrade(Upgrade::Speed));
+    }
+
+    #[test]
+    fn test_docked_robot_is_refitted() {
+        let mut station = Station::new(0, 0);
+        station.research_points = UPGRADE_BASE_COST;
+        stock(&mut station, UPGRADE_RECIPE, 1);
+        assert!(station.research_upgrade(Upgrade::Battery));
+
+        let mut robot = Robot::new(0, 0, Direction::North, RobotRole::Explorer);
+        station.refit_robot(&mut robot);
+        assert_eq!(robot.upgrades.battery, 1);
+        assert_eq!(robot.battery_capacity(), BATTERY_CAPACITY + BATTERY_PER_LEVEL);
+
+        station.recharge_robot(&mut robot);
+        assert_eq!(robot.battery, BATTERY_CAPACITY + STATION_RECHARGE_RATE);
+    }
+
+    fn context<'a>(registry: &'a RoleRegistry, resources: &'a HashMap<Cell, usize>, fleet: &[(RobotRole, usize)]) -> ProductionContext<'a> {
+        ProductionContext {
+            registry,
+            resources,
+            fleet: fleet.iter().copied().collect(),
+            explored: 0.0,
+            known_resources: 0,
+            unanalysed_science: 0,
+        }
+    }
+
+    #[test]
+    fn test_fixed_ratio_policy_fills_the_gap() {
+        let registry = RoleRegistry::default();
+        let resources = HashMap::new();
+        let mut policy = FixedRatioPolicy::new(vec![(RobotRole::Explorer, 1), (RobotRole::Collector, 2)]);
+
+        let ctx = context(&registry, &resources, &[(RobotRole::Explorer, 1), (RobotRole::Collector, 1)]);
+        assert_eq!(policy.next_role(&ctx), Some(RobotRole::Collector));
+        let ctx = context(&registry, &resources, &[(RobotRole::Explorer, 1), (RobotRole::Collector, 2)]);
+        assert_eq!(policy.next_role(&ctx), Some(RobotRole::Explorer));
+    }
+
+    #[test]
+    fn test_demand_driven_policy() {
+        let registry = RoleRegistry::default();
+        let resources = HashMap::new();
+        let mut policy = DemandDrivenPolicy;
+
+        let mut ctx = context(&registry, &resources, &[(RobotRole::Collector, 1)]);
+        ctx.known_resources = RESOURCES_PER_COLLECTOR + 1;
+        assert_eq!(policy.next_role(&ctx), Some(RobotRole::Collector));
+
+        ctx.known_resources = 0;
+        assert_eq!(policy.next_role(&ctx), Some(RobotRole::Explorer));
+
+        // Once the map is explored only a hauler is still missing
+        ctx.explored = 1.0;
+        assert_eq!(policy.next_role(&ctx), Some(RobotRole::Hauler));
+        ctx.fleet.insert(RobotRole::Hauler, 1);
+        assert_eq!(policy.next_role(&ctx), None);
+    }
+
+    #[test]
+    fn test_manual_queue_waits_for_resources() {
+        let registry = RoleRegistry::default();
+        let mut station = Station::new(0, 0);
+        let mut policy = ManualQueuePolicy::new([RobotRole::Scientist, RobotRole::Explorer]);
+        station.resources_collected.insert(Cell::Energy, 50);
+
+        let resources = station.resources_collected.clone();
+        let role = policy.next_role(&context(&registry, &resources, &[])).unwrap();
+        assert_eq!(role, RobotRole::Scientist);
+        assert!(!station.enqueue(role, &registry));
+        assert!(station.production_queue.is_empty());
+        assert_eq!(station.resources_collected[&Cell::Energy], 50);
+
+        policy.on_ordered(RobotRole::Scientist);
+        assert_eq!(policy.queue.front(), Some(&RobotRole::Explorer));
+    }
+
+    #[test]
+    fn test_enqueue_reserves_resources_until_built() {
+        let registry = RoleRegistry::default();
+        let mut station = Station::new(5, 5);
+        stock(&mut station, EXPLORER_RECIPE, 1);
+
+        assert!(station.enqueue(RobotRole::Explorer, &registry));
+        for &(cell, amount) in EXPLORER_RECIPE {
+            assert_eq!(station.resources_collected[&cell], 0);
+            assert_eq!(station.reserved()[&cell], amount);
+        }
+
+        for _ in 1..EXPLORER_BUILD_TIME {
+            assert!(station.advance_production().is_none());
+        }
+        let robot = station.advance_production().expect("explorer should be built");
+        assert_eq!(robot.role, RobotRole::Explorer);
+        assert_eq!(station.robots_created, 1);
+        assert!(station.production_queue.is_empty());
+    }
+
+    #[test]
+    fn test_queue_length_is_limited() {
+        let registry = RoleRegistry::default();
+        let mut station = Station::new(5, 5);
+        stock(&mut station, EXPLORER_RECIPE, PRODUCTION_QUEUE_LENGTH + 1);
+
+        for _ in 0..PRODUCTION_QUEUE_LENGTH {
+            assert!(station.enqueue(RobotRole::Explorer, &registry));
+        }
+        assert!(!station.enqueue(RobotRole::Explorer, &registry));
+        for &(cell, amount) in EXPLORER_RECIPE {
+            assert_eq!(station.resources_collected[&cell], amount);
+        }
+    }
+
+    #[test]
+    fn test_cancel_order_refunds_reserved_resources() {
+        let registry = RoleRegistry::default();
+        let mut station = Station::new(5, 5);
+        stock(&mut station, EXPLORER_RECIPE, 1);
+        station.enqueue(RobotRole::Explorer, &registry);
+
+        let order = station.cancel_order(0).expect("order should exist");
+        assert_eq!(order.role, RobotRole::Explorer);
+        for &(cell, amount) in EXPLORER_RECIPE {
+            assert_eq!(station.resources_collected[&cell], amount);
+        }
+        assert!(station.reserved().is_empty());
+    }
+
+    #[test]
+    fn test_recipe_is_spent_all_or_nothing() {
+        let mut station = Station::new(0, 0);
+        let recipe = [(Cell::Energy, 3), (Cell::Mineral, 2)];
+        station.resources_collected.insert(Cell::Energy, 5);
+        station.resources_collected.insert(Cell::Mineral, 1);
+
+        assert!(!station.spend(&recipe));
+        assert_eq!(station.resources_collected[&Cell::Energy], 5);
+        assert_eq!(station.resources_collected[&Cell::Mineral], 1);
+
+        station.resources_collected.insert(Cell::Mineral, 2);
+        assert!(station.spend(&recipe));
+        assert_eq!(station.resources_collected[&Cell::Energy], 2);
+        assert_eq!(station.resources_collected[&Cell::Mineral], 0);
+    }
+
+    #[test]
+    fn test_upgrade_needs_its_recipe() {
+        let mut station = Station::new(0, 0);
+        station.research_points = UPGRADE_BASE_COST;
+        assert!(!station.research_upgrade(Upgrade::Speed));
+        assert_eq!(station.research_points, UPGRADE_BASE_COST);
+
+        stock(&mut station, UPGRADE_RECIPE, 1);
+        assert!(station.research_upgrade(Upgrade::Speed));
+        assert_eq!(station.upgrade_recipe(Upgrade::Speed), UPGRADE_RECIPE.iter().map(|&(cell, amount)| (cell, 2 * amount)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_claims_are_exclusive_until_they_expire() {
+        let mut map = open_map(10, 10);
+        map.grid[2][2] = Cell::Mineral;
+        let known = HashMap::from([((2, 2), Cell::Mineral), ((4, 4), Cell::Empty)]);
+        let mut board = TaskBoard::default();
+        board.refresh(1, &map, &known);
+        assert_eq!(board.open_tasks(), 1);
+
+        assert!(board.claim((2, 2), RobotId(1)));
+        assert!(!board.claim((2, 2), RobotId(2)));
+        assert_eq!(board.open_tasks(), 0);
+
+        board.refresh(1 + CLAIM_DURATION, &map, &known);
+        assert!(board.claim((2, 2), RobotId(2)));
+        assert_eq!(board.claimed_by(RobotId(1)), None);
+    }
+
+    #[test]
+    fn test_claims_on_depleted_resources_are_dropped() {
+        let mut map = open_map(10, 10);
+        map.grid[2][2] = Cell::Energy;
+        let known = HashMap::from([((2, 2), Cell::Energy)]);
+        let mut board = TaskBoard::default();
+        board.refresh(1, &map, &known);
+        board.claim((2, 2), RobotId(1));
+
+        map.grid[2][2] = Cell::Empty;
+        board.refresh(2, &map, &known);
+        assert!(board.tasks.is_empty());
+        assert!(board.claims.is_empty());
+    }
+
+    #[test]
+    fn test_claims_taken_on_an_outpost_board_are_merged_back() {
+        let mut map = open_map(10, 10);
+        map.grid[2][2] = Cell::Mineral;
+        map.grid[6][6] = Cell::Energy;
+        let known = HashMap::from([((2, 2), Cell::Mineral), ((6, 6), Cell::Energy)]);
+        let mut board = TaskBoard::default();
+        board.refresh(1, &map, &known);
+        board.claim((2, 2), RobotId(1));
+
+        let base = board.claims.clone();
+        let mut outpost = board.clone();
+        outpost.release(RobotId(1));
+        outpost.claim((6, 6), RobotId(2));
+        board.merge_claims(&base, &outpost);
+
+        assert_eq!(board.claimed_by(RobotId(1)), None);
+        assert_eq!(board.claimed_by(RobotId(2)), Some((6, 6)));
+    }
+
+    #[test]
+    fn test_auction_awards_each_task_to_the_lowest_bid() {
+        let mut board = TaskBoard::default();
+        let bid = |robot, position, path_cost, free_capacity| Bid { robot: RobotId(robot), position, path_cost, free_capacity };
+        let won = board.award(vec![
+            bid(1, (2, 2), 4, 4),
+            bid(2, (2, 2), 2, 1), // Closer, but nearly full: worse per unit of cargo
+            bid(1, (6, 6), 3, 4),
+            bid(2, (6, 6), 8, 1),
+        ]);
+
+        assert_eq!(won.len(), 2);
+        assert_eq!(board.claimed_by(RobotId(1)), Some((6, 6)));
+        assert_eq!(board.claimed_by(RobotId(2)), Some((2, 2)));
+    }
+
+    #[test]
+    fn test_storage_capacity_caps_deliveries() {
+        let mut station = Station::new(0, 0);
+        station.storage_capacity.insert(Cell::Mineral, 2);
+        station.receive_resources(vec![Cell::Mineral; 3]);
+        station.receive_resources(vec![Cell::Energy; 3]);
+
+        assert_eq!(station.resources_collected[&Cell::Mineral], 2);
+        assert_eq!(station.free_storage(Cell::Mineral), Some(0));
+        assert_eq!(station.wasted, 1);
+        assert_eq!(station.resources_collected[&Cell::Energy], 3);
+    }
+
+    #[test]
+    fn test_science_lost_to_full_storage_earns_no_research() {
+        let mut station = Station::new(0, 0);
+        station.storage_capacity.insert(Cell::Science, 1);
+        station.receive_resources(vec![Cell::Science; 3]);
+
+        assert_eq!(station.research_points, RESEARCH_POINTS_PER_SCIENCE);
+        assert_eq!(station.scientific_discoveries, 3);
+        assert_eq!(station.wasted, 2);
+    }
+
+    #[test]
+    fn test_upkeep_is_paid_from_energy_until_credit_runs_out() {
+        let mut station = Station::new(0, 0);
+        station.resources_collected.insert(Cell::Energy, 1);
+        let robots = (1.0 / UPKEEP_ENERGY_PER_ROBOT).ceil() as usize;
+
+        assert!(!station.charge_upkeep(robots));
+        assert_eq!(station.resources_collected[&Cell::Energy], 0);
+        assert!(station.upkeep_debt < 1.0);
+
+        let ticks_to_default = ((UPKEEP_CREDIT + 1.0) / (robots as f32 * UPKEEP_ENERGY_PER_ROBOT)).ceil() as usize;
+        let over_credit = (0..ticks_to_default).any(|_| station.charge_upkeep(robots));
+        assert!(over_credit);
+    }
+}